@@ -4,32 +4,59 @@ use crossterm::event::{self, Event, KeyEventKind};
 use ratatui::{
     buffer::Buffer,
     layout::{
-        Constraint::{Fill, Length},
+        Constraint::{Fill, Length, Min},
         Layout, Rect,
     },
     prelude::Backend,
     widgets::Widget,
     Frame, Terminal,
 };
+use std::io;
+use std::time::Duration;
 
+use crate::control::ControlReader;
 use crate::error::TGVError;
-use crate::models::mode::InputMode;
+use crate::models::{
+    consensus::count_bases, mode::InputMode, region::Region, sequence::SequenceAnchor,
+    view::TrackKind,
+};
 use crate::rendering::{
-    render_alignment, render_console, render_coordinates, render_coverage, render_cytobands,
-    render_error, render_help, render_sequence, render_sequence_at_2x, render_track,
+    dim_area, render_alignment, render_beyond_reference, render_consensus, render_console,
+    render_coordinates, render_coverage, render_cytobands, render_error, render_gene_density,
+    render_guides, render_header, render_help, render_palette, render_primers, render_sequence,
+    render_sequence_at_2x, render_splash, render_zoom_hint, GeneTrackRenderer,
 };
 use crate::settings::Settings;
 use crate::states::State;
+use crate::traits::TrackRenderer;
 pub struct App {
     pub state: State,
+
+    /// Control messages from an external tool (--control).
+    control: Option<ControlReader>,
+
+    /// Tracks drawn below the reads and sequence, in order.
+    renderers: Vec<Box<dyn TrackRenderer>>,
 }
 
 // initialization
 impl App {
     pub async fn new(settings: Settings) -> Result<Self, TGVError> {
+        let control = match settings.control_path.as_ref() {
+            Some(control_path) => Some(ControlReader::open(control_path)?),
+            None => None,
+        };
+
+        let renderers: Vec<Box<dyn TrackRenderer>> =
+            vec![Box::new(GeneTrackRenderer::new(settings.reference.clone()))];
+
         let state = State::new(settings).await?;
 
-        Ok(Self { state })
+        Ok(Self {
+            state,
+            control,
+            renderers,
+        })
     }
 }
 
@@ -39,43 +66,67 @@ impl App {
     pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), TGVError> {
         let mut last_frame_mode = InputMode::Normal;
 
+        // Skip redraws while the terminal is unfocused (--pause-on-unfocus).
+        let mut paused = false;
+
         while !self.state.exit {
             let frame_area = terminal.get_frame().area();
             self.state.update_frame_area(frame_area);
 
             if !self.state.initialized() {
-                // Handle the initial messages
+                // Show a loading screen while the initial region loads.
+                terminal
+                    .draw(|frame| {
+                        self.draw(frame);
+                    })
+                    .unwrap();
 
+                // Handle the initial messages
                 self.state
                     .handle(self.state.settings.initial_state_messages.clone())
                     .await?;
             }
 
-            terminal
-                .draw(|frame| {
-                    self.draw(frame);
-                })
-                .unwrap();
+            if !paused {
+                terminal
+                    .draw(|frame| {
+                        self.draw(frame);
+                    })
+                    .unwrap();
+            }
 
             // handle events
             if !self.state.settings.test_mode {
-                match event::read() {
-                    Ok(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press => {
+                let (event, control_messages) = self.next_event();
+
+                for control_message in control_messages {
+                    self.state.handle_control_message(&control_message).await?;
+                }
+
+                match event {
+                    Some(Ok(Event::Key(key_event))) if key_event.kind == KeyEventKind::Press => {
                         self.state.handle_key_event(key_event).await?;
                     }
-                    Ok(Event::Resize(_width, _height)) => {
+                    Some(Ok(Event::Paste(text))) => {
+                        self.state.handle_paste(text).await?;
+                    }
+                    Some(Ok(Event::Resize(_width, _height))) => {
                         self.state.self_correct_viewing_window();
                     }
+                    Some(Ok(Event::FocusLost)) => {
+                        paused = self.state.settings.pause_on_unfocus;
+                    }
+                    Some(Ok(Event::FocusGained)) => {
+                        paused = false;
+                    }
 
                     _ => {}
                 };
             }
 
             // terminal.clear() is needed when the layout changes significantly, or the last frame is burned into the new frame.
-            let need_screen_refresh = ((last_frame_mode == InputMode::Help)
-                && (self.state.input_mode != InputMode::Help))
-                || ((last_frame_mode != InputMode::Help)
-                    && (self.state.input_mode == InputMode::Help))
+            let need_screen_refresh = (last_frame_mode != self.state.input_mode
+                && (last_frame_mode.is_full_screen() || self.state.input_mode.is_full_screen()))
                 || frame_area.width != terminal.get_frame().area().width
                 || frame_area.height != terminal.get_frame().area().height;
 
@@ -92,11 +143,31 @@ impl App {
         Ok(())
     }
 
+    const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Wait for the next terminal event or control messages, whichever comes first.
+    fn next_event(&self) -> (Option<io::Result<Event>>, Vec<String>) {
+        let control = match self.control.as_ref() {
+            Some(control) => control,
+            None => return (Some(event::read()), vec![]),
+        };
+
+        loop {
+            match event::poll(Self::CONTROL_POLL_INTERVAL) {
+                Ok(true) => return (Some(event::read()), control.receive()),
+                Ok(false) => {}
+                Err(e) => return (Some(Err(e)), control.receive()),
+            }
+
+            let control_messages = control.receive();
+            if !control_messages.is_empty() {
+                return (None, control_messages);
+            }
+        }
+    }
+
     /// Draw the app
     pub fn draw(&self, frame: &mut Frame) {
-        if !self.state.initialized() {
-            panic!("The initial window is not initialized");
-        }
         frame.render_widget(self, frame.area());
     }
 
@@ -114,26 +185,72 @@ impl Widget for &App {
             return; // TOO small. Skip rendering to prevent overflow.
         }
 
+        if !self.state.initialized() {
+            render_splash(area, buf);
+            return;
+        }
+
         if self.state.input_mode == InputMode::Help {
             render_help(area, buf);
             return;
         }
 
+        if self.state.input_mode == InputMode::Header {
+            if let Some(header_text) = self.state.header_text() {
+                render_header(area, buf, header_text, self.state.header_scroll());
+            }
+            return;
+        }
+
         let contig_length = self.state.contig_length().unwrap();
         let viewing_window = self.state.viewing_window().unwrap();
         let viewing_region = self.state.viewing_region().unwrap();
-        let [cytoband_area, coordinate_area, coverage_area, alignment_area, sequence_area, track_area, console_area, error_area] =
-            Layout::vertical([
-                Length(2), // cytobands
-                Length(2), // coordinate
-                Length(6), // coverage
-                Fill(1),   // alignment
-                Length(1), // sequence
-                Length(2), // track
-                Length(2), // console
-                Length(2), // error
-            ])
-            .areas(area);
+        let sequence_height = if self.state.settings.haplotype_path.is_some() {
+            2
+        } else {
+            1
+        };
+        // The sequence row (and haplotype) sits either above or below the alignment.
+        // The ruler and the sequence row take Min constraints, which the layout satisfies before
+        // Length and Fill: when the terminal is too short for all rows, other rows shrink first,
+        // so that reads can always be read against positions. With spare rows, Min rows keep their
+        // height and the alignment takes the rest.
+        let (upper_constraint, lower_constraint) = match self.state.settings.sequence_anchor {
+            SequenceAnchor::Top => (Min(sequence_height), Fill(1)),
+            SequenceAnchor::Bottom => (Fill(1), Min(sequence_height)),
+        };
+        let coordinate_height = if self.state.data.liftover.is_some() {
+            3
+        } else {
+            2
+        };
+        let consensus_height = if self.state.settings.consensus { 1 } else { 0 };
+        let mut constraints = vec![
+            Length(2),                // cytobands
+            Min(coordinate_height),   // coordinate
+            Length(6),                // coverage
+            Length(consensus_height), // consensus
+            upper_constraint,         // alignment or sequence
+            lower_constraint,         // sequence or alignment
+        ];
+        constraints.extend(
+            self.renderers
+                .iter()
+                .map(|renderer| Length(renderer.height())), // tracks
+        );
+        constraints.extend([
+            Length(2), // console
+            Length(2), // error
+        ]);
+        let areas = Layout::vertical(constraints).split(area);
+        let [cytoband_area, coordinate_area, coverage_area, consensus_area, upper_area, lower_area] =
+            [areas[0], areas[1], areas[2], areas[3], areas[4], areas[5]];
+        let track_areas = &areas[6..6 + self.renderers.len()];
+        let [console_area, error_area] = [areas[areas.len() - 2], areas[areas.len() - 1]];
+        let (alignment_area, sequence_area) = match self.state.settings.sequence_anchor {
+            SequenceAnchor::Top => (lower_area, upper_area),
+            SequenceAnchor::Bottom => (upper_area, lower_area),
+        };
 
         if let (Some(cytobands), Some(current_cytoband_index)) = (
             self.state.cytobands(),
@@ -148,58 +265,214 @@ impl Widget for &App {
             );
         }
 
-        render_coordinates(&coordinate_area, buf, viewing_window, contig_length).unwrap();
+        if let (Some(gene_density), Some(contig_length)) = (
+            self.state
+                .data
+                .gene_density
+                .get(&viewing_window.contig.full_name()),
+            contig_length,
+        ) {
+            render_gene_density(&cytoband_area, buf, gene_density, contig_length);
+        }
+
+        render_coordinates(
+            &coordinate_area,
+            buf,
+            viewing_window,
+            contig_length,
+            self.state.data.liftover.as_ref(),
+        )
+        .unwrap();
 
         if self.state.settings.bam_path.is_some()
-            && viewing_window.zoom() <= State::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS
+            && viewing_window.zoom() > State::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS
         {
+            // Reads are not loaded at this zoom level. Coverage is still shown if the cached reads
+            // cover the whole viewing region.
+            if let Some(alignment) = &self.state.data.alignment {
+                if alignment.has_complete_data(&viewing_region) {
+                    let filtered_coverage = self.state.quick_filtered_coverage();
+                    render_coverage(
+                        &coverage_area,
+                        buf,
+                        viewing_window,
+                        filtered_coverage.as_ref().unwrap_or(alignment),
+                        &self.state.settings.coverage_baseline,
+                        &self.state.settings.coverage_bin_agg,
+                        self.state.settings.coverage_include_clips,
+                    )
+                    .unwrap();
+                }
+            }
+
+            render_zoom_hint(&alignment_area, buf);
+        } else if self.state.settings.bam_path.is_some() {
             match &self.state.data.alignment {
                 Some(alignment) => {
-                    render_coverage(&coverage_area, buf, viewing_window, alignment).unwrap();
+                    let filtered_coverage = self.state.quick_filtered_coverage();
+                    render_coverage(
+                        &coverage_area,
+                        buf,
+                        viewing_window,
+                        filtered_coverage.as_ref().unwrap_or(alignment),
+                        &self.state.settings.coverage_baseline,
+                        &self.state.settings.coverage_bin_agg,
+                        self.state.settings.coverage_include_clips,
+                    )
+                    .unwrap();
 
-                    render_alignment(&alignment_area, buf, viewing_window, alignment);
+                    if self.state.settings.consensus
+                        && viewing_window.is_basewise()
+                        && alignment.has_complete_data(&viewing_region)
+                    {
+                        let consensus =
+                            count_bases(alignment, &viewing_region, &self.state.read_filter())
+                                .iter()
+                                .map(|counts| {
+                                    counts.consensus(
+                                        self.state.settings.consensus_min_depth,
+                                        self.state.settings.consensus_allele_percent,
+                                    )
+                                })
+                                .collect::<Vec<Option<u8>>>();
+                        render_consensus(
+                            &consensus_area,
+                            buf,
+                            &consensus,
+                            self.state.settings.monochrome,
+                        );
+                    }
+
+                    render_alignment(
+                        &alignment_area,
+                        buf,
+                        viewing_window,
+                        alignment,
+                        self.state.data.sequence.as_ref(),
+                        self.state.settings.monochrome,
+                        self.state.settings.insertions,
+                        &self.state.read_filter(),
+                        self.state.settings.read_names,
+                        self.state.settings.read_coloring,
+                    );
                 }
                 None => {} // TODO: handle error
             }
         }
 
+        // The BAM header can report a longer contig than the reference genome. The sequence is only
+        // drawn up to the end of the reference contig, and the rest is marked.
+        let reference_length = self.state.reference_contig_length().unwrap();
+        let beyond_reference = match (reference_length, contig_length) {
+            (Some(reference_length), Some(contig_length))
+                if contig_length > reference_length && viewing_region.end > reference_length =>
+            {
+                Some(reference_length)
+            }
+            _ => None,
+        };
+        let sequence_region = Region {
+            end: beyond_reference.map_or(viewing_region.end, |reference_length| {
+                usize::min(viewing_region.end, reference_length)
+            }),
+            ..viewing_region.clone()
+        };
+        let has_sequence_region = sequence_region.start <= sequence_region.end;
+
         if self.state.settings.reference.is_some() {
-            if viewing_window.is_basewise() {
+            if has_sequence_region && viewing_window.is_basewise() {
                 match &self.state.data.sequence {
                     Some(sequence) => {
-                        render_sequence(&sequence_area, buf, &viewing_region, sequence).unwrap();
+                        // Codon bands follow the gene in the middle of the window.
+                        let gene = self.state.data.track.as_ref().and_then(|track| {
+                            track.get_gene_at(viewing_window.middle(&sequence_area))
+                        });
+                        render_sequence(
+                            &sequence_area,
+                            buf,
+                            &sequence_region,
+                            sequence,
+                            self.state.data.haplotype.as_ref(),
+                            gene,
+                            self.state.settings.monochrome,
+                        )
+                        .unwrap();
                     }
                     None => {} // TODO: handle error
                 }
-            } else if viewing_window.zoom() == 2 {
+            } else if has_sequence_region && viewing_window.zoom() == 2 {
                 match &self.state.data.sequence {
                     Some(sequence) => {
-                        render_sequence_at_2x(&sequence_area, buf, &viewing_region, sequence)
-                            .unwrap();
+                        render_sequence_at_2x(
+                            &sequence_area,
+                            buf,
+                            &sequence_region,
+                            sequence,
+                            self.state.data.haplotype.as_ref(),
+                            self.state.settings.monochrome,
+                        )
+                        .unwrap();
                     }
                     None => {} // TODO: handle error
                 }
             }
+        }
 
-            match &self.state.data.track {
-                Some(track) => {
-                    render_track(
-                        &track_area,
-                        buf,
-                        viewing_window,
-                        track,
-                        self.state.settings.reference.as_ref(),
-                    );
-                }
-                None => {} // TODO: handle error
+        for (renderer, track_area) in self.renderers.iter().zip(track_areas) {
+            renderer.render(track_area, buf, viewing_window, &self.state.data);
+        }
+
+        if let Some(reference_length) = beyond_reference {
+            render_beyond_reference(&sequence_area, buf, viewing_window, reference_length);
+        }
+
+        for (track, area) in [
+            (TrackKind::Cytobands, cytoband_area),
+            (TrackKind::Coverage, coverage_area),
+            (TrackKind::Reads, alignment_area),
+            (TrackKind::Sequence, sequence_area),
+        ] {
+            dim_area(&area, buf, self.state.track_dim(track));
+        }
+        for (renderer, track_area) in self.renderers.iter().zip(track_areas) {
+            if let Some(track) = renderer.kind() {
+                dim_area(track_area, buf, self.state.track_dim(track));
             }
         }
 
+        if !self.state.data.primers.is_empty() {
+            render_primers(
+                &alignment_area,
+                buf,
+                viewing_window,
+                &viewing_window.contig,
+                &self.state.data.primers,
+                self.state.settings.dim_primer_reads,
+            );
+        }
+
+        render_guides(
+            &coverage_area.union(*track_areas.last().unwrap_or(&lower_area)),
+            buf,
+            viewing_window,
+            &viewing_window.contig,
+            self.state.guides(),
+        );
+
         if self.state.input_mode == InputMode::Command {
             render_console(&console_area, buf, self.state.command_mode_register())
         }
 
-        render_error(&error_area, buf, &self.state.errors);
+        render_error(
+            &error_area,
+            buf,
+            &self.state.errors,
+            self.state.error_scroll(),
+        );
+
+        if self.state.input_mode == InputMode::Palette {
+            render_palette(&alignment_area, buf, self.state.palette_register());
+        }
 
         // TODO: a proper debug widget
     }
@@ -0,0 +1,148 @@
+use crate::error::TGVError;
+use crate::models::{message::StateMessage, region::Region};
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Appends the navigation and commands of a session to a file, one JSON object per line.
+/// Lines are written as they happen, so the log is complete even if the session ends abruptly.
+pub struct AuditLog {
+    file: File,
+}
+
+impl AuditLog {
+    pub fn open(path: &str) -> Result<Self, TGVError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| TGVError::IOError(format!("Cannot open audit log {}: {}", path, e)))?;
+
+        Ok(Self { file })
+    }
+
+    /// Write one entry. region: the viewing region after the message was handled.
+    pub fn record(
+        &mut self,
+        event: &str,
+        message: &str,
+        region: Option<&Region>,
+    ) -> Result<(), TGVError> {
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+
+        let line = get_audit_line(time_ms, event, message, region);
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.flush())
+            .map_err(|e| TGVError::IOError(format!("Cannot write audit log: {}", e)))
+    }
+}
+
+fn get_audit_line(time_ms: u128, event: &str, message: &str, region: Option<&Region>) -> String {
+    json!({
+        "time_ms": time_ms as u64,
+        "event": event,
+        "message": message,
+        "region": region.map(|region| region.to_string()),
+    })
+    .to_string()
+}
+
+/// The kind of audited event, or None if the message is not audited.
+/// Jumps (including tiling steps), view and setting changes, guides, and exports are audited.
+/// Step-wise panning and zooming are not, as they would flood the log.
+pub fn audit_event(message: &StateMessage) -> Option<&'static str> {
+    match message {
+        StateMessage::GotoCoordinate(_)
+        | StateMessage::GotoContig(_)
+        | StateMessage::GotoContigCoordinate(_, _)
+        | StateMessage::GoToGene(_)
+        | StateMessage::GoToVariant(_)
+        | StateMessage::FindFeatures(_)
+        | StateMessage::GoToDefault => Some("goto"),
+
+        StateMessage::GotoNextExonsStart(_)
+        | StateMessage::GotoNextExonsEnd(_)
+        | StateMessage::GotoPreviousExonsStart(_)
+        | StateMessage::GotoPreviousExonsEnd(_)
+        | StateMessage::GotoNextGenesStart(_)
+        | StateMessage::GotoNextGenesEnd(_)
+        | StateMessage::GotoPreviousGenesStart(_)
+        | StateMessage::GotoPreviousGenesEnd(_)
+        | StateMessage::GotoNextGap(_)
+        | StateMessage::GotoPreviousGap(_)
+        | StateMessage::GotoNextMatch(_)
+        | StateMessage::GotoPreviousMatch(_)
+        | StateMessage::GotoNextContig(_)
+        | StateMessage::GotoPreviousContig(_)
+        | StateMessage::SetTileStep(_)
+        | StateMessage::StepForward(_)
+        | StateMessage::StepBackward(_)
+        | StateMessage::FitRead => Some("jump"),
+
+        StateMessage::SaveView(_) | StateMessage::RestoreView(_) | StateMessage::ToggleView => {
+            Some("view")
+        }
+
+        StateMessage::AddGuide(_, _, _) | StateMessage::ClearGuides => Some("guide"),
+
+        StateMessage::SetCoverageBaseline(_)
+        | StateMessage::SetCoverageAggregation(_)
+        | StateMessage::SetCoverageClips(_)
+        | StateMessage::SetSequenceAnchor(_)
+        | StateMessage::SetInsertionDisplay(_)
+        | StateMessage::SetMinMapq(_)
+        | StateMessage::SetMapq255Meaning(_)
+        | StateMessage::SetReadNames(_)
+        | StateMessage::CycleReadColoring
+        | StateMessage::QuickFilter(_)
+        | StateMessage::ClearQuickFilter
+        | StateMessage::SetTrackDim(_, _) => Some("set"),
+
+        StateMessage::ExportPileup(_)
+        | StateMessage::YankSequence
+        | StateMessage::ExportCommandLine => Some("export"),
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::contig::Contig;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(StateMessage::GoToGene("TP53".to_string()), Some("goto"))]
+    #[case(StateMessage::GotoNextGap(1), Some("jump"))]
+    #[case(StateMessage::StepForward(1), Some("jump"))]
+    #[case(StateMessage::SetTileStep(Some(1000)), Some("jump"))]
+    #[case(StateMessage::ExportPileup("out.txt".to_string()), Some("export"))]
+    #[case(StateMessage::SetCoverageClips(true), Some("set"))]
+    #[case(StateMessage::MoveLeft(1), None)]
+    #[case(StateMessage::ZoomIn(2), None)]
+    fn test_audit_event(#[case] message: StateMessage, #[case] expected: Option<&str>) {
+        assert_eq!(audit_event(&message), expected);
+    }
+
+    #[test]
+    fn test_audit_line() {
+        let region = Region {
+            contig: Contig::chrom("chr17"),
+            start: 7572600,
+            end: 7572700,
+        };
+        assert_eq!(
+            get_audit_line(1000, "goto", r#"GoToGene("TP53")"#, Some(&region)),
+            r#"{"event":"goto","message":"GoToGene(\"TP53\")","region":"chr17:7572600-7572700","time_ms":1000}"#
+        );
+        assert_eq!(
+            get_audit_line(1000, "set", "SetCoverageClips(true)", None),
+            r#"{"event":"set","message":"SetCoverageClips(true)","region":null,"time_ms":1000}"#
+        );
+    }
+}
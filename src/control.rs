@@ -0,0 +1,175 @@
+use crate::error::TGVError;
+use crate::models::{message::StateMessage, register::CommandModeRegister};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+/// Reads control messages from a file (typically a named pipe), one JSON object per line.
+/// Lines are read on a background thread and collected by the main loop.
+pub struct ControlReader {
+    receiver: Receiver<String>,
+}
+
+impl ControlReader {
+    pub fn open(path: &str) -> Result<Self, TGVError> {
+        if !Path::new(path).exists() {
+            return Err(TGVError::IOError(format!(
+                "Control file {} not found. Create it with mkfifo.",
+                path
+            )));
+        }
+
+        let is_fifo = is_fifo(path)?;
+        let (sender, receiver) = channel();
+        let path = path.to_string();
+
+        thread::spawn(move || {
+            // Opening a named pipe blocks until a writer connects, and reading ends when the
+            // writer disconnects. Reopen to wait for the next writer. Other files are read once.
+            while let Ok(file) = File::open(&path) {
+                for line in BufReader::new(file).lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(_) => break,
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if sender.send(line).is_err() {
+                        return; // The app has exited.
+                    }
+                }
+
+                if !is_fifo {
+                    return;
+                }
+            }
+        });
+
+        Ok(Self { receiver })
+    }
+
+    /// Lines received since the last call. Does not block.
+    pub fn receive(&self) -> Vec<String> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[cfg(unix)]
+fn is_fifo(path: &str) -> Result<bool, TGVError> {
+    use std::os::unix::fs::FileTypeExt;
+
+    std::fs::metadata(path)
+        .map(|metadata| metadata.file_type().is_fifo())
+        .map_err(|e| TGVError::IOError(format!("Cannot read control file {}: {}", path, e)))
+}
+
+/// Named pipes are only supported on unix.
+#[cfg(not(unix))]
+fn is_fifo(_path: &str) -> Result<bool, TGVError> {
+    Ok(false)
+}
+
+/// Translate a control message to state messages.
+/// Supported keys:
+/// - "goto": a region, gene, or variant, as in :goto. e.g. {"goto": "chr1:1000"}
+/// - "command": any command mode input. e.g. {"command": "guide 1000 breakpoint"}
+/// - "set": settings to change, as in :set. e.g. {"set": {"coverage_bin_agg": "max"}}
+pub fn parse_control_message(line: &str) -> Result<Vec<StateMessage>, String> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| format!("Invalid control message: {}: {}", line, e))?;
+
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return Err(format!("Control message must be a JSON object: {}", line)),
+    };
+
+    let mut messages = Vec::new();
+    for (key, value) in object {
+        match (key.as_str(), value) {
+            ("goto", Value::String(region)) => messages.extend(CommandModeRegister::parse_input(
+                &format!("goto {}", region),
+            )?),
+            ("command", Value::String(command)) => messages.extend(
+                CommandModeRegister::parse_input(command.trim().trim_start_matches(':'))?,
+            ),
+            ("set", Value::Object(settings)) => {
+                for (name, value) in settings {
+                    let value = match value {
+                        Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    messages.extend(CommandModeRegister::parse_input(&format!(
+                        "set {} {}",
+                        name, value
+                    ))?);
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid control message: {}. Supported keys: goto, command, set",
+                    line
+                ))
+            }
+        }
+    }
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::coverage::CoverageAggregation;
+    use rstest::rstest;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    #[rstest]
+    #[case(r#"{"goto": "chr1:1000"}"#, Ok(vec![StateMessage::GotoContigCoordinate("chr1".to_string(), 1000)]))]
+    #[case(r#"{"goto": "TP53"}"#, Ok(vec![StateMessage::GoToGene("TP53".to_string())]))]
+    #[case(r#"{"command": ":guide clear"}"#, Ok(vec![StateMessage::ClearGuides]))]
+    #[case(r#"{"set": {"coverage_bin_agg": "max"}}"#, Ok(vec![StateMessage::SetCoverageAggregation(CoverageAggregation::Max)]))]
+    #[case(r#"{"set": {"unknown": 30}}"#, Err("Unknown setting: unknown".to_string()))]
+    #[case(r#"{"zoom": 2}"#, Err(r#"Invalid control message: {"zoom": 2}. Supported keys: goto, command, set"#.to_string()))]
+    #[case(r#"["goto"]"#, Err(r#"Control message must be a JSON object: ["goto"]"#.to_string()))]
+    fn test_parse_control_message(
+        #[case] line: &str,
+        #[case] expected: Result<Vec<StateMessage>, String>,
+    ) {
+        assert_eq!(parse_control_message(line), expected);
+    }
+
+    #[test]
+    fn test_parse_malformed_control_message() {
+        assert!(parse_control_message("{goto").is_err());
+    }
+
+    #[test]
+    fn test_regular_control_file_is_read_once() {
+        let path = std::env::temp_dir().join(format!("tgv-control-{}.jsonl", std::process::id()));
+        std::fs::write(
+            &path,
+            "{\"goto\": \"chr1:1000\"}\n\n{\"command\": \"gaps\"}\n",
+        )
+        .unwrap();
+
+        // The reader thread drops its sender once it is done with a regular file, which
+        // disconnects the channel. A reader that reopened the file would time out instead.
+        let reader = ControlReader::open(path.to_str().unwrap()).unwrap();
+        let mut lines = Vec::new();
+        loop {
+            match reader.receiver.recv_timeout(Duration::from_secs(10)) {
+                Ok(line) => lines.push(line),
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => panic!("The control file was not read once"),
+            }
+        }
+        assert_eq!(lines.len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,3 +4,102 @@ pub fn is_url(path: &str) -> bool {
         || path.starts_with("https://")
         || path.starts_with("gs://")
 }
+
+/// Split text into lines of at most width characters. Line breaks in the text are kept.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![];
+    }
+
+    text.lines()
+        .flat_map(|line| {
+            let chars = line.chars().collect::<Vec<char>>();
+            if chars.is_empty() {
+                return vec![String::new()];
+            }
+            chars
+                .chunks(width)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Standard base64 with padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// OSC 52 escape sequence that asks the terminal to set the clipboard.
+pub fn osc52_copy_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Quote a word for a POSIX shell. Words of safe characters are kept as they are.
+pub fn shell_quote(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c);
+    if !word.is_empty() && word.chars().all(is_safe) {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", 4, vec![])]
+    #[case("abc", 4, vec!["abc"])]
+    #[case("abcdefghij", 4, vec!["abcd", "efgh", "ij"])]
+    #[case("ab\n\ncdefg", 4, vec!["ab", "", "cdef", "g"])]
+    #[case("abc", 0, vec![])]
+    fn test_wrap_text(#[case] text: &str, #[case] width: usize, #[case] expected: Vec<&str>) {
+        assert_eq!(wrap_text(text, width), expected);
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("A", "QQ==")]
+    #[case("AC", "QUM=")]
+    #[case("ACG", "QUNH")]
+    #[case("ACGTN", "QUNHVE4=")]
+    fn test_base64_encode(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(base64_encode(text.as_bytes()), expected);
+    }
+
+    #[rstest]
+    #[case("input.bam", "input.bam")]
+    #[case("s3://bucket/input.bam", "s3://bucket/input.bam")]
+    #[case("7:140753336 A>T", "'7:140753336 A>T'")]
+    #[case("it's.bam", "'it'\\''s.bam'")]
+    #[case("", "''")]
+    fn test_shell_quote(#[case] word: &str, #[case] expected: &str) {
+        assert_eq!(shell_quote(word), expected);
+    }
+
+    #[test]
+    fn test_osc52_copy_sequence() {
+        assert_eq!(osc52_copy_sequence("ACG"), "\x1b]52;c;QUNH\x07");
+    }
+}
@@ -1,4 +1,6 @@
 mod app;
+mod audit;
+mod control;
 mod error;
 mod helpers;
 mod models;
@@ -8,27 +10,43 @@ mod states;
 mod traits;
 use app::App;
 use clap::Parser;
+use crossterm::{
+    event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange},
+    execute,
+};
 use error::TGVError;
 use settings::{Cli, Settings};
+use std::io::stdout;
 
 #[tokio::main]
 async fn main() -> Result<(), TGVError> {
     let cli = Cli::parse();
-    let settings: Settings = Settings::new(cli, false).unwrap();
+    let mut settings: Settings = Settings::new(cli, false).unwrap();
+    // See https://no-color.org: a non-empty NO_COLOR disables colors.
+    if std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty()) {
+        settings.monochrome = true;
+    }
 
     let mut terminal = ratatui::init();
+    let _ = execute!(stdout(), EnableBracketedPaste);
+    if settings.pause_on_unfocus {
+        // Inert on terminals that do not report focus changes.
+        let _ = execute!(stdout(), EnableFocusChange);
+    }
 
     // TODO: initialize UCSC connections here to ensure that they are properly closed in case of errors.
 
     let mut app = match App::new(settings).await {
         Ok(app) => app,
         Err(e) => {
+            let _ = execute!(stdout(), DisableBracketedPaste, DisableFocusChange);
             ratatui::restore();
             return Err(e);
         }
     };
     let app_result = app.run(&mut terminal).await;
 
+    let _ = execute!(stdout(), DisableBracketedPaste, DisableFocusChange);
     ratatui::restore();
     app.close().await?;
     app_result
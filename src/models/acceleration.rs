@@ -0,0 +1,123 @@
+use crossterm::event::KeyCode;
+use std::time::{Duration, Instant};
+
+/// Speeds up panning while a movement key is held down.
+/// Terminals report a held key as rapid repeated key presses. When the same key is pressed again
+/// within REPEAT_INTERVAL, the repeat is counted and the pan distance grows with the number of
+/// repeats, up to MAX_FACTOR.
+pub struct PanAccelerator {
+    last_key: Option<KeyCode>,
+    last_time: Option<Instant>,
+    repeats: usize,
+}
+
+impl Default for PanAccelerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PanAccelerator {
+    /// Key presses further apart than this are not considered a held key.
+    pub const REPEAT_INTERVAL: Duration = Duration::from_millis(150);
+
+    /// Number of repeats before the pan distance grows by one step.
+    pub const REPEATS_PER_STEP: usize = 5;
+
+    pub const MAX_FACTOR: usize = 16;
+
+    pub fn new() -> Self {
+        Self {
+            last_key: None,
+            last_time: None,
+            repeats: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.last_key = None;
+        self.last_time = None;
+        self.repeats = 0;
+    }
+
+    /// Register a pan key press at time now. Returns the factor to multiply the pan distance by.
+    pub fn register(&mut self, key: KeyCode, now: Instant) -> usize {
+        let is_repeat = match (self.last_key, self.last_time) {
+            (Some(last_key), Some(last_time)) => {
+                last_key == key && now.saturating_duration_since(last_time) <= Self::REPEAT_INTERVAL
+            }
+            _ => false,
+        };
+
+        if is_repeat {
+            self.repeats += 1;
+        } else {
+            self.repeats = 0;
+        }
+
+        self.last_key = Some(key);
+        self.last_time = Some(now);
+
+        (1 + self.repeats / Self::REPEATS_PER_STEP).min(Self::MAX_FACTOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(5, 1)]
+    #[case(6, 2)]
+    #[case(11, 3)]
+    #[case(1000, PanAccelerator::MAX_FACTOR)]
+    fn test_held_key_acceleration(#[case] n_presses: usize, #[case] expected_factor: usize) {
+        let mut accelerator = PanAccelerator::new();
+        let start = Instant::now();
+
+        let mut factor = 0;
+        for i in 0..n_presses {
+            factor = accelerator.register(
+                KeyCode::Char('l'),
+                start + Duration::from_millis(30 * i as u64),
+            );
+        }
+        assert_eq!(factor, expected_factor);
+    }
+
+    #[test]
+    fn test_acceleration_stops() {
+        let mut accelerator = PanAccelerator::new();
+        let start = Instant::now();
+
+        for i in 0..20 {
+            accelerator.register(
+                KeyCode::Char('l'),
+                start + Duration::from_millis(30 * i as u64),
+            );
+        }
+
+        // Slow press
+        assert_eq!(
+            accelerator.register(KeyCode::Char('l'), start + Duration::from_secs(10)),
+            1
+        );
+
+        // Different key
+        for i in 0..20 {
+            accelerator.register(
+                KeyCode::Char('l'),
+                start + Duration::from_secs(20) + Duration::from_millis(30 * i as u64),
+            );
+        }
+        assert_eq!(
+            accelerator.register(
+                KeyCode::Char('h'),
+                start + Duration::from_secs(20) + Duration::from_millis(630)
+            ),
+            1
+        );
+    }
+}
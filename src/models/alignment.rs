@@ -1,10 +1,13 @@
 use crate::error::TGVError;
 use crate::helpers::is_url;
-use crate::models::{contig::Contig, region::Region};
+use crate::models::{contig::Contig, coverage::LibraryType, region::Region, strand::Strand};
+use clap::ValueEnum;
 use rust_htslib::bam;
 use rust_htslib::bam::ext::BamRecordExtensions;
+use rust_htslib::bam::record::Cigar;
 use rust_htslib::bam::{Header, IndexedReader, Read, Record};
 use std::collections::{BTreeMap, HashMap};
+use strum::Display;
 use url::Url;
 
 /// An aligned read with viewing coordinates.
@@ -26,6 +29,12 @@ pub struct AlignedRead {
     /// Trailing softclips. Used for track stacking calculation.
     pub trailing_softclips: usize,
 
+    /// Leading hardclips, outside the leading softclips. Used for track stacking calculation.
+    pub leading_hardclips: usize,
+
+    /// Trailing hardclips, outside the trailing softclips. Used for track stacking calculation.
+    pub trailing_hardclips: usize,
+
     /// Y coordinate in the alignment view
     /// 0-based.
     pub y: usize,
@@ -37,12 +46,266 @@ impl AlignedRead {
         self.start..self.end + 1
     }
 
+    /// Insertions in the read. Returns the 1-based reference position before each insertion and
+    /// the inserted bases.
+    pub fn insertions(&self) -> Vec<(usize, Vec<u8>)> {
+        let mut output = Vec::new();
+        let bases = self.read.seq().as_bytes();
+
+        let mut reference_pivot = self.start;
+        let mut query_pivot = 0;
+
+        for op in self.read.cigar().iter() {
+            let length = op.len() as usize;
+            match op {
+                Cigar::Ins(_) => {
+                    // Reads without a stored sequence (SEQ *) have no inserted bases to show.
+                    if let (true, Some(inserted)) = (
+                        reference_pivot > 1,
+                        bases.get(query_pivot..query_pivot + length),
+                    ) {
+                        output.push((reference_pivot - 1, inserted.to_vec()));
+                    }
+                    query_pivot += length;
+                }
+                Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) => {
+                    reference_pivot += length;
+                    query_pivot += length;
+                }
+                Cigar::Del(_) | Cigar::RefSkip(_) => reference_pivot += length,
+                Cigar::SoftClip(_) => query_pivot += length,
+                Cigar::HardClip(_) | Cigar::Pad(_) => {}
+            }
+        }
+
+        output
+    }
+
     fn stacking_start(&self) -> usize {
-        usize::max(self.start.saturating_sub(self.leading_softclips), 1)
+        usize::max(
+            self.start
+                .saturating_sub(self.leading_softclips + self.leading_hardclips),
+            1,
+        )
     }
 
     fn stacking_end(&self) -> usize {
-        self.end.saturating_add(self.trailing_softclips)
+        self.end
+            .saturating_add(self.trailing_softclips + self.trailing_hardclips)
+    }
+}
+
+/// How insertions are drawn at base resolution.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum InsertionDisplay {
+    /// Not drawn.
+    Hidden,
+
+    /// A marker with the insertion length on the base before the insertion. :ins shows the
+    /// inserted bases at the middle of the view.
+    Collapsed,
+}
+
+/// Open a local or remote BAM file. A remote BAM file with a local index (e.g. from the index
+/// cache) is opened with htslib's "##idx##" syntax.
+pub fn open_indexed_reader(
+    bam_path: &String,
+    bai_path: Option<&String>,
+) -> Result<IndexedReader, TGVError> {
+    let is_remote_path = is_url(bam_path);
+    match bai_path {
+        Some(bai_path) => {
+            if is_remote_path {
+                let path_with_index = format!("{}##idx##{}", bam_path, bai_path);
+                IndexedReader::from_url(
+                    &Url::parse(&path_with_index).map_err(|e| TGVError::IOError(e.to_string()))?,
+                )
+                .map_err(|e| TGVError::IOError(e.to_string()))
+            } else {
+                IndexedReader::from_path_and_index(bam_path, bai_path)
+                    .map_err(|e| TGVError::IOError(e.to_string()))
+            }
+        }
+        None => {
+            if is_remote_path {
+                IndexedReader::from_url(
+                    &Url::parse(bam_path).map_err(|e| TGVError::IOError(e.to_string()))?,
+                )
+                .map_err(|e| TGVError::IOError(e.to_string()))
+            } else {
+                IndexedReader::from_path(bam_path).map_err(|e| TGVError::IOError(e.to_string()))
+            }
+        }
+    }
+}
+
+/// What reads are colored by. Cycled with C.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReadColoring {
+    /// Reads are gray.
+    Plain,
+
+    /// Reads are tinted by strand: forward reads red, reverse reads blue. Bases stay readable.
+    StrandTint,
+}
+
+impl ReadColoring {
+    pub const ALL: [ReadColoring; 2] = [ReadColoring::Plain, ReadColoring::StrandTint];
+
+    pub fn next(&self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|coloring| coloring == self)
+            .unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// What a MAPQ of 255 means. The SAM spec reserves 255 for "unavailable", but some aligners
+/// write 255 for confidently placed reads.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Mapq255Meaning {
+    /// The mapping quality is unknown. Such reads fail any minimum MAPQ.
+    Unavailable,
+
+    /// The highest mapping quality. Such reads pass any minimum MAPQ.
+    Max,
+}
+
+/// Keeps or hides reads by the base they carry at one position (f / F).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BaseFilter {
+    /// 1-based.
+    pub position: usize,
+
+    /// Uppercase.
+    pub base: u8,
+
+    /// true: only reads carrying the base are shown. false: reads carrying the base are hidden.
+    pub carries: bool,
+}
+
+/// Which reads are drawn, counted in the consensus, and exported.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ReadFilter {
+    /// 0: no MAPQ filter.
+    pub min_mapq: u8,
+    pub mapq_255_meaning: Mapq255Meaning,
+
+    /// Quick filter on the current contig. None: no base filter.
+    pub base: Option<BaseFilter>,
+}
+
+impl ReadFilter {
+    pub fn admits(&self, read: &Record) -> bool {
+        self.admits_mapq(read) && self.admits_base(read)
+    }
+
+    fn admits_mapq(&self, read: &Record) -> bool {
+        if self.min_mapq == 0 {
+            return true;
+        }
+
+        match (read.mapq(), self.mapq_255_meaning) {
+            (255, Mapq255Meaning::Unavailable) => false,
+            (255, Mapq255Meaning::Max) => true,
+            (mapq, _) => mapq >= self.min_mapq,
+        }
+    }
+
+    /// Reads that do not cover the position (or have a deletion there) do not carry the base.
+    fn admits_base(&self, read: &Record) -> bool {
+        match self.base {
+            Some(filter) => {
+                let carries = base_at(read, filter.position)
+                    .is_some_and(|base| base.to_ascii_uppercase() == filter.base);
+                carries == filter.carries
+            }
+            None => true,
+        }
+    }
+}
+
+/// Aligned base of the read at a 1-based reference position. None if the position is not covered
+/// by an aligned base (outside the read, in a deletion, or in a skipped region).
+pub fn base_at(read: &Record, position: usize) -> Option<u8> {
+    let mut reference_pivot = read.pos() as usize + 1;
+    let mut query_pivot = 0;
+
+    for op in read.cigar().iter() {
+        let length = op.len() as usize;
+        match op {
+            Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) => {
+                if position >= reference_pivot && position < reference_pivot + length {
+                    // Reads without a stored sequence (SEQ *) carry no base.
+                    let i_query = query_pivot + position - reference_pivot;
+                    if i_query >= read.seq_len() {
+                        return None;
+                    }
+                    return Some(read.seq()[i_query]);
+                }
+                reference_pivot += length;
+                query_pivot += length;
+            }
+            Cigar::Del(_) | Cigar::RefSkip(_) => {
+                if position < reference_pivot + length {
+                    return None;
+                }
+                reference_pivot += length;
+            }
+            Cigar::Ins(_) | Cigar::SoftClip(_) => query_pivot += length,
+            Cigar::HardClip(_) | Cigar::Pad(_) => {}
+        }
+
+        if reference_pivot > position {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// Clip lengths at both ends of a read.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ClipLengths {
+    pub leading_softclips: usize,
+    pub trailing_softclips: usize,
+    pub leading_hardclips: usize,
+    pub trailing_hardclips: usize,
+}
+
+impl ClipLengths {
+    /// By SAM spec, hard clips can only be the outermost operations, e.g. 5H3S90M2S.
+    /// Note that htslib's leading_softclips() and trailing_softclips() only look at the outermost
+    /// operations, and miss softclips inside hardclips.
+    pub fn from_cigar(cigar: &[Cigar]) -> Self {
+        let mut clips = Self::default();
+
+        let mut leading = cigar.iter().peekable();
+        if let Some(Cigar::HardClip(l)) = leading.peek() {
+            clips.leading_hardclips = *l as usize;
+            leading.next();
+        }
+        if let Some(Cigar::SoftClip(l)) = leading.peek() {
+            clips.leading_softclips = *l as usize;
+        }
+
+        if cigar.len() < 2 {
+            return clips; // A single clip operation is counted as leading.
+        }
+
+        let mut trailing = cigar.iter().rev().peekable();
+        if let Some(Cigar::HardClip(l)) = trailing.peek() {
+            clips.trailing_hardclips = *l as usize;
+            trailing.next();
+        }
+        if let Some(Cigar::SoftClip(l)) = trailing.peek() {
+            clips.trailing_softclips = *l as usize;
+        }
+
+        clips
     }
 }
 
@@ -55,6 +318,14 @@ pub struct Alignment {
     /// Coverage at each position. Keys are 1-based, inclusive.
     coverage: BTreeMap<usize, usize>,
 
+    /// Coverage split by inferred transcript strand. Only computed for stranded libraries.
+    /// Keys are 1-based, inclusive.
+    strand_coverage: Option<(BTreeMap<usize, usize>, BTreeMap<usize, usize>)>,
+
+    /// Number of soft-clipped bases of stacked reads over each position. Not included in
+    /// coverage. Keys are 1-based, inclusive.
+    clipped_coverage: BTreeMap<usize, usize>,
+
     /// The left bound of region with complete data.
     /// 1-based, inclusive.
     data_complete_left_bound: usize,
@@ -78,6 +349,12 @@ pub struct Alignment {
     /// The rightmost position in each alignment track.
     /// 1-based, inclusive.
     track_right_bounds: Vec<usize>,
+
+    /// Approximate memory held by the stacked reads, in bytes.
+    memory_usage: usize,
+
+    /// Number of reads that were counted in coverage but not stacked due to the memory budget.
+    downsampled_reads: usize,
 }
 
 impl Alignment {
@@ -85,6 +362,8 @@ impl Alignment {
         Self {
             reads: Vec::new(),
             coverage: BTreeMap::new(),
+            strand_coverage: None,
+            clipped_coverage: BTreeMap::new(),
             track_left_bounds: Vec::new(),
             track_right_bounds: Vec::new(),
             contig: contig.clone(),
@@ -92,37 +371,23 @@ impl Alignment {
             track_most_right_bound: 0,
             data_complete_left_bound: 0,
             data_complete_right_bound: 0,
+            memory_usage: 0,
+            downsampled_reads: 0,
         }
     }
 
+    /// Load alignments in a region.
+    /// memory_budget: soft limit (in bytes) of memory used by stacked reads. Once the budget is
+    /// approached, reads are downsampled. Reads beyond the budget are only counted in coverage.
+    /// library_type: for stranded libraries, coverage is also computed per transcript strand.
     pub fn from_bam_path(
         bam_path: &String,
         bai_path: Option<&String>,
         region: &Region,
+        memory_budget: Option<usize>,
+        library_type: &LibraryType,
     ) -> Result<Self, TGVError> {
-        let is_remote_path = is_url(bam_path);
-        let mut bam = match bai_path {
-            Some(bai_path) => {
-                if is_remote_path {
-                    return Err(TGVError::IOError(
-                        "Remote BAM files are not supported yet.".to_string(),
-                    ));
-                }
-                IndexedReader::from_path_and_index(bam_path, bai_path)
-                    .map_err(|e| TGVError::IOError(e.to_string()))?
-            }
-            None => {
-                if is_remote_path {
-                    IndexedReader::from_url(
-                        &Url::parse(bam_path).map_err(|e| TGVError::IOError(e.to_string()))?,
-                    )
-                    .unwrap()
-                } else {
-                    IndexedReader::from_path(bam_path)
-                        .map_err(|e| TGVError::IOError(e.to_string()))?
-                }
-            }
-        };
+        let mut bam = open_indexed_reader(bam_path, bai_path)?;
 
         let header = bam::Header::from_template(bam.header());
 
@@ -136,22 +401,47 @@ impl Alignment {
 
         let mut alignment = Self::new(&region.contig);
         let mut coverage_hashmap: HashMap<usize, usize> = HashMap::new(); // First use a hashmap to store coverage, then convert to BTreeMap
+        let mut strand_coverage: (BTreeMap<usize, usize>, BTreeMap<usize, usize>) =
+            (BTreeMap::new(), BTreeMap::new());
 
-        for record in bam.records() {
+        for (i_record, record) in bam.records().enumerate() {
             let read = record.map_err(|e| TGVError::IOError(e.to_string()))?;
+
+            if let Some(strand) = library_type.transcript_strand(&read) {
+                let coverage = match strand {
+                    Strand::Forward => &mut strand_coverage.0,
+                    Strand::Reverse => &mut strand_coverage.1,
+                };
+                for i in read.pos() as usize + 1..read.reference_end() as usize + 1 {
+                    *coverage.entry(i).or_insert(0) += 1;
+                }
+            }
+
+            // Downsampled reads still count towards coverage, but not soft-clipped coverage.
+            if !alignment.admits_read(&read, i_record, memory_budget) {
+                alignment.downsampled_reads += 1;
+                for i in read.pos() as usize + 1..read.reference_end() as usize + 1 {
+                    *coverage_hashmap.entry(i).or_insert(0) += 1;
+                }
+                continue;
+            }
+
+            Self::add_clipped_coverage(&mut alignment.clipped_coverage, &read);
+            alignment.memory_usage += Self::approximate_read_memory(&read);
             alignment.add_read(read);
             let aligned_read = alignment.reads.last().unwrap();
 
             // update coverage hashmap
             for i in aligned_read.range() {
-                // TODO: check exclusivity here
-                *coverage_hashmap.entry(i).or_insert(1) += 1;
+                *coverage_hashmap.entry(i).or_insert(0) += 1;
             }
         }
 
         // Convert hashmap to BTreeMap
-        for (k, v) in coverage_hashmap {
-            *alignment.coverage.entry(k).or_insert(v) += v;
+        alignment.coverage.extend(coverage_hashmap);
+
+        if library_type.is_stranded() {
+            alignment.strand_coverage = Some(strand_coverage);
         }
 
         alignment.data_complete_left_bound = region.start;
@@ -160,6 +450,24 @@ impl Alignment {
         Ok(alignment)
     }
 
+    /// Count the soft-clipped bases of a read at the positions they would align to.
+    fn add_clipped_coverage(clipped_coverage: &mut BTreeMap<usize, usize>, read: &Record) {
+        let read_start = read.pos() as usize + 1;
+        let read_end = read.reference_end() as usize;
+        let ClipLengths {
+            leading_softclips,
+            trailing_softclips,
+            ..
+        } = ClipLengths::from_cigar(&read.cigar());
+
+        for i in usize::max(read_start.saturating_sub(leading_softclips), 1)..read_start {
+            *clipped_coverage.entry(i).or_insert(0) += 1;
+        }
+        for i in read_end + 1..read_end + trailing_softclips + 1 {
+            *clipped_coverage.entry(i).or_insert(0) += 1;
+        }
+    }
+
     /// Get the query string for a region.
     /// Look through the header to decide if the bam file chromosome names are abbreviated or full.
     fn get_query_contig_string(header: &Header, region: &Region) -> Result<String, TGVError> {
@@ -236,6 +544,210 @@ impl Alignment {
     }
 }
 
+/// Soft-clipped coverage
+impl Alignment {
+    /// Number of soft-clipped bases at position.
+    /// 1-based, inclusive.
+    pub fn clipped_coverage_at(&self, pos: usize) -> usize {
+        if pos < self.data_complete_left_bound || pos > self.data_complete_right_bound {
+            return 0;
+        }
+        self.clipped_coverage.get(&pos).copied().unwrap_or(0)
+    }
+
+    /// Mean number of soft-clipped bases in [left, right].
+    /// 1-based, inclusive.
+    pub fn mean_clipped_coverage_in(&self, left: usize, right: usize) -> Result<usize, TGVError> {
+        if right < left {
+            return Err(TGVError::ValueError("Right is less than left".to_string()));
+        }
+
+        if right < self.data_complete_left_bound || left > self.data_complete_right_bound {
+            return Ok(0);
+        }
+
+        Ok(self
+            .clipped_coverage
+            .range(left..right + 1)
+            .map(|(_, coverage)| coverage)
+            .sum::<usize>()
+            / (right - left + 1))
+    }
+}
+
+/// Insertions
+impl Alignment {
+    /// Sequences inserted after the 1-based position by reads admitted by the filter, with the
+    /// number of reads for each. Most common first; ties are sorted by sequence.
+    pub fn insertions_after(&self, position: usize, filter: &ReadFilter) -> Vec<(Vec<u8>, usize)> {
+        let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+        for read in self
+            .reads
+            .iter()
+            .filter(|read| read.start <= position && read.end >= position)
+            .filter(|read| filter.admits(&read.read))
+        {
+            for (_, bases) in read
+                .insertions()
+                .into_iter()
+                .filter(|(insertion_position, _)| *insertion_position == position)
+            {
+                *counts.entry(bases).or_insert(0) += 1;
+            }
+        }
+
+        let mut insertions = counts.into_iter().collect::<Vec<(Vec<u8>, usize)>>();
+        insertions.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        insertions
+    }
+}
+
+/// Filtered coverage
+impl Alignment {
+    /// An alignment without reads, whose coverage counts only the loaded reads admitted by the
+    /// filter. Reads downsampled by the memory guard are not loaded, so they are not counted.
+    /// library_type: for stranded libraries, coverage is also computed per transcript strand.
+    pub fn filtered_coverage(&self, filter: &ReadFilter, library_type: &LibraryType) -> Self {
+        let mut alignment = Self::new(&self.contig);
+        alignment.data_complete_left_bound = self.data_complete_left_bound;
+        alignment.data_complete_right_bound = self.data_complete_right_bound;
+        if self.is_stranded() {
+            alignment.strand_coverage = Some((BTreeMap::new(), BTreeMap::new()));
+        }
+
+        for read in self.reads.iter().filter(|read| filter.admits(&read.read)) {
+            for i in read.range() {
+                *alignment.coverage.entry(i).or_insert(0) += 1;
+            }
+
+            if let (Some((forward, reverse)), Some(strand)) = (
+                alignment.strand_coverage.as_mut(),
+                library_type.transcript_strand(&read.read),
+            ) {
+                let coverage = match strand {
+                    Strand::Forward => forward,
+                    Strand::Reverse => reverse,
+                };
+                for i in read.range() {
+                    *coverage.entry(i).or_insert(0) += 1;
+                }
+            }
+
+            Self::add_clipped_coverage(&mut alignment.clipped_coverage, &read.read);
+        }
+
+        alignment
+    }
+}
+
+/// Stranded coverage
+impl Alignment {
+    /// Whether coverage is split by transcript strand.
+    pub fn is_stranded(&self) -> bool {
+        self.strand_coverage.is_some()
+    }
+
+    fn strand_coverage_map(&self, strand: &Strand) -> Option<&BTreeMap<usize, usize>> {
+        self.strand_coverage
+            .as_ref()
+            .map(|(forward, reverse)| match strand {
+                Strand::Forward => forward,
+                Strand::Reverse => reverse,
+            })
+    }
+
+    /// Basewise coverage of reads from a transcript strand at position.
+    /// 0 if coverage is not stranded.
+    /// 1-based, inclusive.
+    pub fn strand_coverage_at(&self, pos: usize, strand: &Strand) -> usize {
+        if pos < self.data_complete_left_bound || pos > self.data_complete_right_bound {
+            return 0;
+        }
+        self.strand_coverage_map(strand)
+            .and_then(|coverage| coverage.get(&pos))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Mean basewise coverage of reads from a transcript strand in [left, right].
+    /// 1-based, inclusive.
+    pub fn mean_strand_coverage_in(
+        &self,
+        left: usize,
+        right: usize,
+        strand: &Strand,
+    ) -> Result<usize, TGVError> {
+        if right < left {
+            return Err(TGVError::ValueError("Right is less than left".to_string()));
+        }
+
+        let coverage = match self.strand_coverage_map(strand) {
+            Some(coverage) => coverage,
+            None => return Ok(0),
+        };
+
+        let left = left.max(self.data_complete_left_bound);
+        let right = right.min(self.data_complete_right_bound);
+        if right < left {
+            return Ok(0);
+        }
+
+        Ok(coverage
+            .range(left..right + 1)
+            .map(|(_, coverage)| coverage)
+            .sum::<usize>()
+            / (right - left + 1))
+    }
+}
+
+/// Memory guard
+impl Alignment {
+    /// Reads are downsampled by half once memory usage reaches DOWNSAMPLING_THRESHOLD_PERCENT of the budget.
+    const DOWNSAMPLING_THRESHOLD_PERCENT: usize = 80;
+
+    /// Approximate memory footprint of a read in bytes.
+    fn approximate_read_memory(read: &Record) -> usize {
+        std::mem::size_of::<AlignedRead>()
+            + read.qname().len()
+            + 4 * read.cigar_len() // cigar ops are u32
+            + read.seq_len().div_ceil(2) // 4-bit encoded bases
+            + read.seq_len() // base qualities
+    }
+
+    /// Whether a read should be stacked given the memory budget.
+    fn admits_read(&self, read: &Record, i_record: usize, memory_budget: Option<usize>) -> bool {
+        let budget = match memory_budget {
+            Some(budget) => budget,
+            None => return true,
+        };
+
+        if self.memory_usage + Self::approximate_read_memory(read) > budget {
+            return false;
+        }
+
+        if self.memory_usage >= budget / 100 * Self::DOWNSAMPLING_THRESHOLD_PERCENT {
+            return i_record % 2 == 0;
+        }
+
+        true
+    }
+
+    /// Approximate memory held by the stacked reads, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Number of reads not stacked due to the memory budget.
+    pub fn downsampled_reads(&self) -> usize {
+        self.downsampled_reads
+    }
+
+    /// Whether some reads were dropped from the pileup due to the memory budget.
+    pub fn is_downsampled(&self) -> bool {
+        self.downsampled_reads > 0
+    }
+}
+
 /// Read stacking
 impl Alignment {
     const MIN_HORIZONTAL_GAP_BETWEEN_READS: usize = 3;
@@ -265,22 +777,23 @@ impl Alignment {
     fn add_read(&mut self, read: Record) {
         let read_start = read.pos() as usize + 1;
         let read_end = read.reference_end() as usize;
-        let leading_softclips = read.cigar().leading_softclips() as usize;
-        let trailing_softclips = read.cigar().trailing_softclips() as usize;
+        let clips = ClipLengths::from_cigar(&read.cigar());
         // read.pos() in htslib: 0-based, inclusive, excluding leading hardclips and softclips
         // read.reference_end() in htslib: 0-based, exclusive, excluding trailing hardclips and softclips
 
         let y = self.find_track(
-            read_start.saturating_sub(leading_softclips),
-            read_end.saturating_add(trailing_softclips),
+            read_start.saturating_sub(clips.leading_softclips + clips.leading_hardclips),
+            read_end.saturating_add(clips.trailing_softclips + clips.trailing_hardclips),
         );
 
         let aligned_read = AlignedRead {
             read,
             start: read_start,
             end: read_end,
-            leading_softclips,
-            trailing_softclips,
+            leading_softclips: clips.leading_softclips,
+            trailing_softclips: clips.trailing_softclips,
+            leading_hardclips: clips.leading_hardclips,
+            trailing_hardclips: clips.trailing_hardclips,
             y,
         };
 
@@ -311,3 +824,339 @@ impl Alignment {
         self.reads.push(aligned_read);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use rust_htslib::bam::record::CigarString;
+
+    #[rstest]
+    #[case(vec![Cigar::Match(10)], (0, 0, 0, 0))]
+    #[case(vec![Cigar::SoftClip(3), Cigar::Match(10), Cigar::SoftClip(2)], (3, 2, 0, 0))]
+    #[case(vec![Cigar::HardClip(5), Cigar::SoftClip(3), Cigar::Match(10)], (3, 0, 5, 0))]
+    #[case(vec![Cigar::Match(10), Cigar::SoftClip(2), Cigar::HardClip(7)], (0, 2, 0, 7))]
+    #[case(vec![Cigar::HardClip(5), Cigar::Match(10), Cigar::HardClip(7)], (0, 0, 5, 7))]
+    fn test_clip_lengths(
+        #[case] cigar: Vec<Cigar>,
+        #[case] expected: (usize, usize, usize, usize),
+    ) {
+        let clips = ClipLengths::from_cigar(&cigar);
+        assert_eq!(
+            (
+                clips.leading_softclips,
+                clips.trailing_softclips,
+                clips.leading_hardclips,
+                clips.trailing_hardclips
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0, Mapq255Meaning::Unavailable, 0, true)]
+    #[case(30, Mapq255Meaning::Unavailable, 29, false)]
+    #[case(30, Mapq255Meaning::Unavailable, 30, true)]
+    #[case(30, Mapq255Meaning::Unavailable, 255, false)]
+    #[case(30, Mapq255Meaning::Max, 255, true)]
+    #[case(0, Mapq255Meaning::Unavailable, 255, true)]
+    fn test_read_filter_mapq(
+        #[case] min_mapq: u8,
+        #[case] mapq_255_meaning: Mapq255Meaning,
+        #[case] mapq: u8,
+        #[case] expected: bool,
+    ) {
+        let mut record = Record::new();
+        record.set_mapq(mapq);
+        let filter = ReadFilter {
+            min_mapq,
+            mapq_255_meaning,
+            base: None,
+        };
+        assert_eq!(filter.admits(&record), expected);
+    }
+
+    /// 2S3M1D2M1I2M at position 10: aligned bases ACG at 10-12, a deletion at 13, TT at 14-15,
+    /// and GC at 16-17 after the insertion.
+    fn base_filter_record() -> Record {
+        let mut record = Record::new();
+        let cigar = CigarString(vec![
+            Cigar::SoftClip(2),
+            Cigar::Match(3),
+            Cigar::Del(1),
+            Cigar::Match(2),
+            Cigar::Ins(1),
+            Cigar::Match(2),
+        ]);
+        record.set(b"read1", Some(&cigar), b"NNACGTTAGC", &[30; 10]);
+        record.set_pos(9);
+        record
+    }
+
+    #[rstest]
+    #[case(9, None)] // soft clip
+    #[case(10, Some(b'A'))]
+    #[case(12, Some(b'G'))]
+    #[case(13, None)] // deletion
+    #[case(14, Some(b'T'))]
+    #[case(16, Some(b'G'))] // after the insertion
+    #[case(17, Some(b'C'))]
+    #[case(18, None)]
+    fn test_base_at(#[case] position: usize, #[case] expected: Option<u8>) {
+        assert_eq!(base_at(&base_filter_record(), position), expected);
+    }
+
+    #[test]
+    fn test_base_at_without_sequence() {
+        let mut record = Record::new();
+        let cigar = CigarString(vec![Cigar::Match(5)]);
+        record.set(b"read1", Some(&cigar), b"", &[]);
+        record.set_pos(9);
+        assert_eq!(base_at(&record, 12), None);
+    }
+
+    #[test]
+    fn test_insertions() {
+        // 2S3M2I2M1D1M12I1M at position 10.
+        let mut record = Record::new();
+        let cigar = CigarString(vec![
+            Cigar::SoftClip(2),
+            Cigar::Match(3),
+            Cigar::Ins(2),
+            Cigar::Match(2),
+            Cigar::Del(1),
+            Cigar::Match(1),
+            Cigar::Ins(12),
+            Cigar::Match(1),
+        ]);
+        let sequence = b"TTACGGGACCAAAAAAAAAAAAT";
+        record.set(b"read1", Some(&cigar), sequence, &[30; 23]);
+        record.set_pos(9);
+
+        let mut alignment = Alignment::new(&Contig::chrom("chr1"));
+        alignment.add_read(record);
+        assert_eq!(
+            alignment.reads[0].insertions(),
+            vec![(12, b"GG".to_vec()), (16, b"AAAAAAAAAAAA".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_insertions_after() {
+        let record = |cigar: Vec<Cigar>, sequence: &[u8], mapq: u8| {
+            let mut record = Record::new();
+            record.set(
+                b"read1",
+                Some(&CigarString(cigar)),
+                sequence,
+                &vec![30; sequence.len()],
+            );
+            record.set_pos(9);
+            record.set_mapq(mapq);
+            record
+        };
+
+        // Reads at position 10 with A, A, and AT inserted after 15, no insertion, and A inserted
+        // by a read hidden by the filter.
+        let mut alignment = Alignment::new(&Contig::chrom("chr1"));
+        for (cigar, sequence, mapq) in [
+            (
+                vec![Cigar::Match(6), Cigar::Ins(1), Cigar::Match(2)],
+                &b"ACGTTTAGC"[..],
+                60,
+            ),
+            (
+                vec![Cigar::Match(6), Cigar::Ins(1), Cigar::Match(2)],
+                &b"ACGTTTAGC"[..],
+                60,
+            ),
+            (
+                vec![Cigar::Match(6), Cigar::Ins(2), Cigar::Match(2)],
+                &b"ACGTTTATGC"[..],
+                60,
+            ),
+            (vec![Cigar::Match(8)], &b"ACGTTTGC"[..], 60),
+            (
+                vec![Cigar::Match(6), Cigar::Ins(1), Cigar::Match(2)],
+                &b"ACGTTTAGC"[..],
+                0,
+            ),
+        ] {
+            alignment.add_read(record(cigar, sequence, mapq));
+        }
+
+        let filter = ReadFilter {
+            min_mapq: 1,
+            mapq_255_meaning: Mapq255Meaning::Unavailable,
+            base: None,
+        };
+        assert_eq!(
+            alignment.insertions_after(15, &filter),
+            vec![(b"A".to_vec(), 2), (b"AT".to_vec(), 1)]
+        );
+        assert_eq!(alignment.insertions_after(14, &filter), vec![]);
+    }
+
+    #[rstest]
+    #[case(11, b'C', true, true)]
+    #[case(11, b'C', false, false)]
+    #[case(11, b'T', true, false)]
+    #[case(11, b'T', false, true)]
+    #[case(13, b'C', true, false)] // the deletion does not carry the base
+    #[case(100, b'C', false, true)] // not covered
+    fn test_read_filter_base(
+        #[case] position: usize,
+        #[case] base: u8,
+        #[case] carries: bool,
+        #[case] expected: bool,
+    ) {
+        let filter = ReadFilter {
+            min_mapq: 0,
+            mapq_255_meaning: Mapq255Meaning::Unavailable,
+            base: Some(BaseFilter {
+                position,
+                base,
+                carries,
+            }),
+        };
+        assert_eq!(filter.admits(&base_filter_record()), expected);
+    }
+
+    /// Memory budget and usage are in units of the test read's memory.
+    #[rstest]
+    #[case(None, 0, 1, true)] // no budget
+    #[case(Some(100), 0, 1, true)]
+    #[case(Some(100), 79, 1, true)] // below the threshold
+    #[case(Some(100), 80, 0, true)] // above the threshold: every other read
+    #[case(Some(100), 80, 1, false)]
+    #[case(Some(100), 99, 0, true)]
+    #[case(Some(100), 100, 0, false)] // budget exhausted
+    fn test_admits_read(
+        #[case] budget: Option<usize>,
+        #[case] usage: usize,
+        #[case] i_record: usize,
+        #[case] expected: bool,
+    ) {
+        let read = base_filter_record();
+        let read_memory = Alignment::approximate_read_memory(&read);
+
+        let mut alignment = Alignment::new(&Contig::chrom("chr1"));
+        alignment.memory_usage = usage * read_memory;
+        assert_eq!(
+            alignment.admits_read(&read, i_record, budget.map(|budget| budget * read_memory)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(LibraryType::Unstranded)]
+    #[case(LibraryType::FrFirststrand)]
+    fn test_coverage_is_read_depth(#[case] library_type: LibraryType) {
+        let bam_path = env!("CARGO_MANIFEST_DIR").to_string() + "/tests/data/covid.sorted.bam";
+        let region = Region {
+            contig: Contig::contig("MN908947.3"),
+            start: 1,
+            end: 200,
+        };
+        let alignment =
+            Alignment::from_bam_path(&bam_path, None, &region, None, &library_type).unwrap();
+
+        // All 95 reads cover position 50.
+        assert_eq!(alignment.coverage_at(50), 95);
+
+        for position in [1, 50, 100, 150] {
+            let depth = alignment
+                .reads
+                .iter()
+                .filter(|read| read.start <= position && position <= read.end)
+                .count();
+            assert_eq!(alignment.coverage_at(position), depth);
+
+            if library_type.is_stranded() {
+                assert_eq!(
+                    alignment.strand_coverage_at(position, &Strand::Forward)
+                        + alignment.strand_coverage_at(position, &Strand::Reverse),
+                    depth
+                );
+            }
+        }
+    }
+
+    /// Each read counts once, whether it is stacked or downsampled.
+    #[rstest]
+    #[case(None)]
+    #[case(Some(1))] // every read is downsampled
+    fn test_coverage_counts_each_read_once(#[case] memory_budget: Option<usize>) {
+        let bam_path = env!("CARGO_MANIFEST_DIR").to_string() + "/tests/data/covid.sorted.bam";
+        let region = Region {
+            contig: Contig::contig("MN908947.3"),
+            start: 1,
+            end: 200,
+        };
+        let alignment = Alignment::from_bam_path(
+            &bam_path,
+            None,
+            &region,
+            memory_budget,
+            &LibraryType::Unstranded,
+        )
+        .unwrap();
+
+        assert_eq!(alignment.reads.len() + alignment.downsampled_reads(), 95);
+        // All 95 reads cover position 50.
+        assert_eq!(alignment.coverage_at(50), 95);
+    }
+
+    #[test]
+    fn test_add_clipped_coverage() {
+        // 2S3M1S at position 10: soft clips over 8-9 and 13.
+        let mut record = Record::new();
+        let cigar = CigarString(vec![
+            Cigar::SoftClip(2),
+            Cigar::Match(3),
+            Cigar::SoftClip(1),
+        ]);
+        record.set(b"read1", Some(&cigar), b"ACGTAC", &[30; 6]);
+        record.set_pos(9);
+
+        let mut clipped_coverage = BTreeMap::new();
+        Alignment::add_clipped_coverage(&mut clipped_coverage, &record);
+        Alignment::add_clipped_coverage(&mut clipped_coverage, &record);
+        assert_eq!(clipped_coverage, BTreeMap::from([(8, 2), (9, 2), (13, 2)]));
+    }
+
+    #[test]
+    fn test_filtered_coverage() {
+        let bam_path = env!("CARGO_MANIFEST_DIR").to_string() + "/tests/data/covid.sorted.bam";
+        let region = Region {
+            contig: Contig::contig("MN908947.3"),
+            start: 1,
+            end: 200,
+        };
+        let library_type = LibraryType::Unstranded;
+        let alignment =
+            Alignment::from_bam_path(&bam_path, None, &region, None, &library_type).unwrap();
+
+        let admit_all = ReadFilter {
+            min_mapq: 0,
+            mapq_255_meaning: Mapq255Meaning::Unavailable,
+            base: None,
+        };
+        let coverage = alignment.filtered_coverage(&admit_all, &library_type);
+        assert!(coverage.reads.is_empty());
+        for position in [1, 50, 100, 150] {
+            assert_eq!(
+                coverage.coverage_at(position),
+                alignment.coverage_at(position)
+            );
+        }
+
+        let hide_all = ReadFilter {
+            min_mapq: 255,
+            mapq_255_meaning: Mapq255Meaning::Unavailable,
+            base: None,
+        };
+        let coverage = alignment.filtered_coverage(&hide_all, &library_type);
+        assert_eq!(coverage.coverage_at(50), 0);
+    }
+}
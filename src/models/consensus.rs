@@ -0,0 +1,185 @@
+use crate::models::{
+    alignment::{Alignment, ReadFilter},
+    region::Region,
+};
+use rust_htslib::bam::record::Cigar;
+
+/// Bases with a lower base quality are not counted in the consensus.
+pub const MIN_BASE_QUALITY: u8 = 13;
+
+const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
+/// Counts of A, C, G, and T at one position.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct BaseCounts([usize; 4]);
+
+impl BaseCounts {
+    /// Other bases (e.g. N) are ignored.
+    pub fn add(&mut self, base: u8) {
+        if let Some(i) = BASES.iter().position(|b| *b == base.to_ascii_uppercase()) {
+            self.0[i] += 1;
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    /// Most common base other than the excluded one. None if no other base is counted.
+    /// Ties keep ACGT order.
+    pub fn most_common_except(&self, excluded: Option<u8>) -> Option<u8> {
+        let mut best: Option<(u8, usize)> = None;
+        for (base, count) in BASES.iter().zip(self.0.iter()) {
+            if Some(*base) == excluded || *count == 0 {
+                continue;
+            }
+            match best {
+                Some((_, best_count)) if best_count >= *count => {}
+                _ => best = Some((*base, *count)),
+            }
+        }
+        best.map(|(base, _)| base)
+    }
+
+    /// Consensus base. None if the depth is below min_depth.
+    /// If the second most common base makes up at least allele_percent of the depth, the IUPAC
+    /// code of the two most common bases is returned, so that heterozygous sites stand out.
+    pub fn consensus(&self, min_depth: usize, allele_percent: u8) -> Option<u8> {
+        let depth = self.depth();
+        if depth == 0 || depth < min_depth {
+            return None;
+        }
+
+        let mut ranked = [0, 1, 2, 3];
+        ranked.sort_by_key(|i| std::cmp::Reverse(self.0[*i])); // stable: ties keep ACGT order
+        let (first, second) = (ranked[0], ranked[1]);
+
+        if self.0[second] > 0 && self.0[second] * 100 >= depth * allele_percent as usize {
+            Some(iupac_code(BASES[first], BASES[second]))
+        } else {
+            Some(BASES[first])
+        }
+    }
+}
+
+/// IUPAC ambiguity code for two different bases.
+pub fn iupac_code(a: u8, b: u8) -> u8 {
+    match (a.min(b), a.max(b)) {
+        (b'A', b'C') => b'M',
+        (b'A', b'G') => b'R',
+        (b'A', b'T') => b'W',
+        (b'C', b'G') => b'S',
+        (b'C', b'T') => b'Y',
+        (b'G', b'T') => b'K',
+        _ => b'N',
+    }
+}
+
+/// Whether the base is an IUPAC ambiguity code of two bases.
+pub fn is_ambiguity_code(base: u8) -> bool {
+    matches!(base, b'M' | b'R' | b'W' | b'S' | b'Y' | b'K')
+}
+
+/// Base counts at each position of the region, from aligned bases of the loaded reads.
+/// Soft clips, insertions, bases below MIN_BASE_QUALITY, and reads not admitted by the filter are
+/// not counted.
+pub fn count_bases(alignment: &Alignment, region: &Region, filter: &ReadFilter) -> Vec<BaseCounts> {
+    let mut counts = vec![BaseCounts::default(); region.width()];
+
+    for read in alignment
+        .reads
+        .iter()
+        .filter(|read| read.start <= region.end && read.end >= region.start)
+        .filter(|read| filter.admits(&read.read))
+    {
+        let sequence = read.read.seq().as_bytes();
+        let qualities = read.read.qual();
+        let mut reference_pivot = read.start; // 1-based
+        let mut query_pivot = 0;
+
+        for op in read.read.cigar().iter() {
+            let length = op.len() as usize;
+            match op {
+                Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) => {
+                    for i in 0..length {
+                        let position = reference_pivot + i;
+                        if position < region.start || position > region.end {
+                            continue;
+                        }
+                        // Reads without qualities store 0xff.
+                        let quality = qualities.get(query_pivot + i).copied().unwrap_or(0xff);
+                        if quality != 0xff && quality < MIN_BASE_QUALITY {
+                            continue;
+                        }
+                        // Reads without a stored sequence (SEQ *) have no bases to count.
+                        if let Some(base) = sequence.get(query_pivot + i) {
+                            counts[position - region.start].add(*base);
+                        }
+                    }
+                    reference_pivot += length;
+                    query_pivot += length;
+                }
+                Cigar::Del(_) | Cigar::RefSkip(_) => reference_pivot += length,
+                Cigar::Ins(_) | Cigar::SoftClip(_) => query_pivot += length,
+                Cigar::HardClip(_) | Cigar::Pad(_) => {}
+            }
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn counts(a: usize, c: usize, g: usize, t: usize) -> BaseCounts {
+        BaseCounts([a, c, g, t])
+    }
+
+    #[rstest]
+    #[case(counts(10, 0, 0, 0), Some(b'A'))]
+    #[case(counts(9, 1, 0, 0), Some(b'A'))] // 10% is below the threshold
+    #[case(counts(6, 0, 4, 0), Some(b'R'))]
+    #[case(counts(0, 5, 0, 5), Some(b'Y'))]
+    #[case(counts(0, 0, 8, 2), Some(b'K'))] // exactly at the threshold
+    #[case(counts(2, 1, 0, 0), None)] // below the minimum depth
+    #[case(counts(0, 0, 0, 0), None)]
+    fn test_consensus(#[case] counts: BaseCounts, #[case] expected: Option<u8>) {
+        assert_eq!(counts.consensus(4, 20), expected);
+    }
+
+    #[rstest]
+    #[case(b'A', b'C', b'M')]
+    #[case(b'T', b'A', b'W')]
+    #[case(b'G', b'C', b'S')]
+    #[case(b'A', b'A', b'N')]
+    fn test_iupac_code(#[case] a: u8, #[case] b: u8, #[case] expected: u8) {
+        assert_eq!(iupac_code(a, b), expected);
+    }
+
+    #[rstest]
+    #[case(counts(10, 0, 3, 0), Some(b'A'), Some(b'G'))]
+    #[case(counts(10, 2, 2, 0), Some(b'A'), Some(b'C'))] // tie
+    #[case(counts(10, 0, 0, 0), Some(b'A'), None)]
+    #[case(counts(10, 0, 3, 0), None, Some(b'A'))]
+    #[case(counts(0, 0, 0, 0), None, None)]
+    fn test_most_common_except(
+        #[case] counts: BaseCounts,
+        #[case] excluded: Option<u8>,
+        #[case] expected: Option<u8>,
+    ) {
+        assert_eq!(counts.most_common_except(excluded), expected);
+    }
+
+    #[test]
+    fn test_base_counts_add() {
+        let mut counts = BaseCounts::default();
+        for base in b"ACgtNA" {
+            counts.add(*base);
+        }
+        assert_eq!(counts, BaseCounts([2, 1, 1, 1]));
+        assert_eq!(counts.depth(), 5);
+    }
+}
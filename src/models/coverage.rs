@@ -0,0 +1,97 @@
+use crate::models::strand::Strand;
+use clap::ValueEnum;
+use rust_htslib::bam::Record;
+use strum::Display;
+
+/// Where coverage bars grow from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum CoverageBaseline {
+    /// Bars grow upwards from the bottom of the coverage area.
+    Bottom,
+
+    /// Bars grow from a zero line in the middle of the coverage area.
+    /// Negative values extend below the zero line.
+    Middle,
+}
+
+/// How coverage is aggregated when multiple bases map to one column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum CoverageAggregation {
+    /// Mean coverage of the bases. Shows the overall level.
+    Mean,
+
+    /// Maximum coverage of the bases. Spikes are not missed.
+    Max,
+
+    /// Minimum coverage of the bases. Dropouts are not missed.
+    Min,
+}
+
+/// Strandedness of an RNA-seq library. Used to infer the transcript strand of each read.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum LibraryType {
+    /// Coverage is not split by strand.
+    Unstranded,
+
+    /// Read 1 maps to the reverse strand of the transcript (e.g. dUTP, TruSeq Stranded).
+    FrFirststrand,
+
+    /// Read 1 maps to the forward strand of the transcript (e.g. Ligation, SOLiD).
+    FrSecondstrand,
+}
+
+impl LibraryType {
+    pub fn is_stranded(&self) -> bool {
+        *self != LibraryType::Unstranded
+    }
+
+    /// Strand of the transcript that a read originates from, from its flags.
+    /// Unpaired reads are treated as read 1. None for unstranded libraries.
+    pub fn transcript_strand(&self, record: &Record) -> Option<Strand> {
+        let is_read_2 = record.is_paired() && record.is_last_in_template();
+
+        // For first-strand libraries, read 1 on the reverse strand means a forward transcript.
+        let is_forward_in_firststrand = record.is_reverse() != is_read_2;
+
+        match self {
+            LibraryType::Unstranded => None,
+            LibraryType::FrFirststrand if is_forward_in_firststrand => Some(Strand::Forward),
+            LibraryType::FrFirststrand => Some(Strand::Reverse),
+            LibraryType::FrSecondstrand if is_forward_in_firststrand => Some(Strand::Reverse),
+            LibraryType::FrSecondstrand => Some(Strand::Forward),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const PAIRED: u16 = 0x1;
+    const REVERSE: u16 = 0x10;
+    const READ_1: u16 = 0x40;
+    const READ_2: u16 = 0x80;
+
+    #[rstest]
+    #[case(LibraryType::FrFirststrand, PAIRED | READ_1 | REVERSE, Some(Strand::Forward))]
+    #[case(LibraryType::FrFirststrand, PAIRED | READ_1, Some(Strand::Reverse))]
+    #[case(LibraryType::FrFirststrand, PAIRED | READ_2, Some(Strand::Forward))]
+    #[case(LibraryType::FrFirststrand, PAIRED | READ_2 | REVERSE, Some(Strand::Reverse))]
+    #[case(LibraryType::FrFirststrand, REVERSE, Some(Strand::Forward))]
+    #[case(LibraryType::FrSecondstrand, PAIRED | READ_1, Some(Strand::Forward))]
+    #[case(LibraryType::FrSecondstrand, PAIRED | READ_1 | REVERSE, Some(Strand::Reverse))]
+    #[case(LibraryType::FrSecondstrand, PAIRED | READ_2 | REVERSE, Some(Strand::Forward))]
+    #[case(LibraryType::FrSecondstrand, 0, Some(Strand::Forward))]
+    #[case(LibraryType::Unstranded, PAIRED | READ_1 | REVERSE, None)]
+    fn test_transcript_strand(
+        #[case] library_type: LibraryType,
+        #[case] flags: u16,
+        #[case] expected: Option<Strand>,
+    ) {
+        let mut record = Record::new();
+        record.set_flags(flags);
+        assert_eq!(library_type.transcript_strand(&record), expected);
+    }
+}
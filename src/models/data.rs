@@ -2,13 +2,23 @@ use crate::error::TGVError;
 use crate::helpers::is_url;
 use crate::models::{
     alignment::Alignment,
+    contig::Contig,
+    coverage::LibraryType,
+    density::GeneDensity,
+    gap::GapIndex,
+    liftover::Liftover,
     message::DataMessage,
+    primer::Primer,
     region::Region,
     sequence::Sequence,
-    services::{sequences::SequenceService, tracks::TrackService},
+    services::{
+        sequences::{FastaSequenceService, SequenceService},
+        tracks::TrackService,
+    },
     track::Track,
 };
 use crate::settings::Settings;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::path::Path;
 /// Holds all data in the session.
 pub struct Data {
@@ -17,13 +27,44 @@ pub struct Data {
     pub bam_path: Option<String>,
     pub bai_path: Option<String>,
 
+    /// Soft memory budget for alignments, in bytes.
+    memory_budget: Option<usize>,
+
+    /// Strandedness of the library, for strand-split coverage.
+    library_type: LibraryType,
+
+    /// Warning raised by the memory guard during the last alignment load.
+    pub memory_warning: Option<String>,
+
     /// Tracks.
     pub track: Option<Track>,
     pub track_service: Option<TrackService>,
 
+    /// Assembly gaps, cached by contig name.
+    gaps: HashMap<String, GapIndex>,
+
+    /// Gene density over whole contigs, cached by contig name.
+    pub gene_density: HashMap<String, GeneDensity>,
+
     /// Sequences.
     pub sequence: Option<Sequence>,
     pub sequence_service: Option<SequenceService>,
+
+    /// Second haplotype sequences, displayed below the reference sequences.
+    pub haplotype: Option<Sequence>,
+    pub haplotype_service: Option<FastaSequenceService>,
+
+    /// Contigs that could not be read from the haplotype FASTA. Their haplotype row is left blank.
+    haplotype_missing_contigs: HashSet<String>,
+
+    /// Warning raised when the haplotype sequence could not be loaded.
+    pub haplotype_warning: Option<String>,
+
+    /// Primer footprints from the primer BED, on all contigs.
+    pub primers: Vec<Primer>,
+
+    /// Positions in another build, from the chain file.
+    pub liftover: Option<Liftover>,
     // TODO: in the first implementation, refresh all data when the viewing window is near the boundary.
 }
 
@@ -70,14 +111,48 @@ impl Data {
             None => (None, None),
         };
 
+        let haplotype_service = match settings.haplotype_path.as_ref() {
+            Some(haplotype_path) => {
+                if !Path::new(&format!("{}.fai", haplotype_path)).exists() {
+                    return Err(TGVError::IOError(format!(
+                        "FASTA index file {}.fai not found. Only indexed FASTA files are supported.",
+                        haplotype_path
+                    )));
+                }
+                Some(FastaSequenceService::new(haplotype_path)?)
+            }
+            None => None,
+        };
+
+        let primers = match settings.primer_path.as_ref() {
+            Some(primer_path) => Primer::from_bed_path(primer_path, settings.reference.as_ref())?,
+            None => Vec::new(),
+        };
+
+        let liftover = match settings.chain_path.as_ref() {
+            Some(chain_path) => Some(Liftover::from_chain_path(chain_path)?),
+            None => None,
+        };
+
         Ok(Self {
             alignment: None,
             bam_path,
             bai_path: settings.bai_path.clone(),
+            memory_budget: settings.max_memory_mb.map(|mb| mb * 1024 * 1024),
+            library_type: settings.library_type,
+            memory_warning: None,
             track: None,
             track_service,
+            gaps: HashMap::new(),
+            gene_density: HashMap::new(),
             sequence: None,
             sequence_service,
+            haplotype: None,
+            haplotype_service,
+            haplotype_missing_contigs: HashSet::new(),
+            haplotype_warning: None,
+            primers,
+            liftover,
         })
     }
 
@@ -118,10 +193,30 @@ impl Data {
                 let bam_path = self.bam_path.as_ref().unwrap();
 
                 if !self.has_complete_alignment(&region) {
-                    self.alignment = Some(
-                        Alignment::from_bam_path(bam_path, self.bai_path.as_ref(), &region)
-                            .unwrap(),
-                    );
+                    if self.memory_budget.is_some() {
+                        // Evict the cached alignment before loading to avoid holding both in memory.
+                        self.alignment = None;
+                    }
+
+                    let alignment = Alignment::from_bam_path(
+                        bam_path,
+                        self.bai_path.as_ref(),
+                        &region,
+                        self.memory_budget,
+                        &self.library_type,
+                    )
+                    .unwrap();
+
+                    if alignment.is_downsampled() {
+                        self.memory_warning = Some(format!(
+                            "Memory budget reached (~{} MB used): {} reads downsampled in {}",
+                            alignment.memory_usage() / (1024 * 1024),
+                            alignment.downsampled_reads(),
+                            region
+                        ));
+                    }
+
+                    self.alignment = Some(alignment);
                     loaded_data = true;
                 }
             }
@@ -136,13 +231,41 @@ impl Data {
                     loaded_data = true;
                 }
             }
+            DataMessage::RequiresGeneDensity(contig) => {
+                if self.track_service.is_none() {
+                    return Err(TGVError::IOError("Track service not found".to_string()));
+                }
+                let track_service = self.track_service.as_ref().unwrap();
+
+                if !self.has_gene_density(&contig) {
+                    let gene_density = track_service.query_gene_density(&contig).await?;
+                    self.gene_density.insert(contig.full_name(), gene_density);
+                    loaded_data = true;
+                }
+            }
             DataMessage::RequiresCompleteSequences(region) => {
                 if self.sequence_service.is_none() {
                     return Err(TGVError::IOError("Sequence service not found".to_string()));
                 }
                 let sequence_service = self.sequence_service.as_ref().unwrap();
 
-                if !self.has_complete_sequence(&region) {
+                if let Some(haplotype_service) = self.haplotype_service.as_ref() {
+                    if !self.has_complete_haplotype(&region) {
+                        match haplotype_service.query_sequence(&region) {
+                            Ok(haplotype) => self.haplotype = Some(haplotype),
+                            Err(e) => {
+                                self.haplotype = None;
+                                self.haplotype_missing_contigs
+                                    .insert(region.contig.full_name());
+                                self.haplotype_warning =
+                                    Some(format!("{}. The haplotype row is left blank.", e));
+                            }
+                        }
+                        loaded_data = true;
+                    }
+                }
+
+                if !self.has_complete_reference_sequence(&region) {
                     match sequence_service.query_sequence(&region).await {
                         Ok(sequence) => {
                             self.sequence = Some(sequence);
@@ -159,6 +282,20 @@ impl Data {
         Ok(loaded_data)
     }
 
+    /// Assembly gaps on a contig. Queried once per contig.
+    pub async fn gap_index(&mut self, contig: &Contig) -> Result<&GapIndex, TGVError> {
+        let track_service = match self.track_service.as_ref() {
+            Some(track_service) => track_service,
+            None => return Err(TGVError::IOError("Track service not found".to_string())),
+        };
+
+        let gap_index = match self.gaps.entry(contig.full_name()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(track_service.query_gaps(contig).await?),
+        };
+        Ok(gap_index)
+    }
+
     pub async fn load_all_data(&mut self, region: Region) -> Result<bool, TGVError> {
         let loaded_alignment = self
             .handle_data_message(DataMessage::RequiresCompleteAlignments(region.clone()))
@@ -180,7 +317,26 @@ impl Data {
         self.track.is_some() && self.track.as_ref().unwrap().has_complete_data(region)
     }
 
+    pub fn has_gene_density(&self, contig: &Contig) -> bool {
+        self.gene_density.contains_key(&contig.full_name())
+    }
+
+    /// Whether the reference sequence, and haplotype sequence if provided, are complete.
     pub fn has_complete_sequence(&self, region: &Region) -> bool {
+        self.has_complete_reference_sequence(region)
+            && (self.haplotype_service.is_none() || self.has_complete_haplotype(region))
+    }
+
+    fn has_complete_reference_sequence(&self, region: &Region) -> bool {
         self.sequence.is_some() && self.sequence.as_ref().unwrap().has_complete_data(region)
     }
+
+    /// Contigs missing from the haplotype FASTA count as complete, so that they are not queried
+    /// again.
+    fn has_complete_haplotype(&self, region: &Region) -> bool {
+        (self.haplotype.is_some() && self.haplotype.as_ref().unwrap().has_complete_data(region))
+            || self
+                .haplotype_missing_contigs
+                .contains(&region.contig.full_name())
+    }
 }
@@ -0,0 +1,51 @@
+use crate::models::contig::Contig;
+
+/// Gene starts on a whole contig, for a density overview at genome scale.
+pub struct GeneDensity {
+    pub contig: Contig,
+
+    /// Sorted. 1-based.
+    starts: Vec<usize>,
+}
+
+impl GeneDensity {
+    pub fn new(contig: Contig, mut starts: Vec<usize>) -> Self {
+        starts.sort();
+        Self { contig, starts }
+    }
+
+    /// Number of genes starting in each of n_bins equal bins over [1, contig_length].
+    /// Genes starting beyond the contig length are counted in the last bin.
+    pub fn bin_counts(&self, contig_length: usize, n_bins: usize) -> Vec<usize> {
+        let mut counts = vec![0; n_bins];
+        if n_bins == 0 || contig_length == 0 {
+            return counts;
+        }
+
+        for start in self.starts.iter() {
+            let i_bin = (start.saturating_sub(1) * n_bins / contig_length).min(n_bins - 1);
+            counts[i_bin] += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(100, 4, vec![2, 0, 1, 2])]
+    #[case(100, 1, vec![5])]
+    #[case(100, 0, vec![])]
+    #[case(0, 2, vec![0, 0])]
+    fn test_bin_counts(
+        #[case] contig_length: usize,
+        #[case] n_bins: usize,
+        #[case] expected: Vec<usize>,
+    ) {
+        let density = GeneDensity::new(Contig::chrom("chr1"), vec![90, 1, 25, 60, 120]);
+        assert_eq!(density.bin_counts(contig_length, n_bins), expected);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::models::contig::Contig;
+use strum::Display;
+
+/// Annotation attributes that can be searched with :find.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum FeatureAttribute {
+    GeneName,
+    TranscriptId,
+    Strand,
+}
+
+impl FeatureAttribute {
+    pub const ALL: [FeatureAttribute; 3] = [
+        FeatureAttribute::GeneName,
+        FeatureAttribute::TranscriptId,
+        FeatureAttribute::Strand,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|attribute| attribute.to_string() == name)
+            .copied()
+    }
+
+    /// Column in the UCSC gene table.
+    pub fn column(&self) -> &'static str {
+        match self {
+            FeatureAttribute::GeneName => "name2",
+            FeatureAttribute::TranscriptId => "name",
+            FeatureAttribute::Strand => "strand",
+        }
+    }
+}
+
+/// An attribute=value query over annotation features.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FeatureQuery {
+    pub attribute: FeatureAttribute,
+    pub value: String,
+}
+
+impl FeatureQuery {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let usage = || {
+            format!(
+                "Invalid find: {}. Usage: find {}=value",
+                input,
+                FeatureAttribute::ALL
+                    .iter()
+                    .map(|attribute| attribute.to_string())
+                    .collect::<Vec<String>>()
+                    .join("|")
+            )
+        };
+
+        let (name, value) = input.split_once('=').ok_or_else(usage)?;
+        let attribute = FeatureAttribute::from_name(name.trim()).ok_or_else(usage)?;
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(usage());
+        }
+
+        Ok(Self {
+            attribute,
+            value: value.to_string(),
+        })
+    }
+}
+
+/// A feature matching a query.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FeatureMatch {
+    pub contig: Contig,
+
+    /// 1-based.
+    pub start: usize,
+
+    pub name: String,
+}
+
+/// Features matching a query, sorted by contig and start. n / N cycle through them.
+pub struct FeatureMatches {
+    pub query: FeatureQuery,
+
+    matches: Vec<FeatureMatch>,
+
+    /// Index of the current match.
+    current: usize,
+}
+
+impl FeatureMatches {
+    pub fn new(query: FeatureQuery, mut matches: Vec<FeatureMatch>) -> Self {
+        matches
+            .sort_by(|a, b| (a.contig.full_name(), a.start).cmp(&(b.contig.full_name(), b.start)));
+
+        Self {
+            query,
+            matches,
+            current: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    /// 0-based index of the current match.
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Select the first match on the contig at or after the position, or the first match on
+    /// the contig, or the first match overall.
+    pub fn select_first(&mut self, contig: &Contig, position: usize) -> Option<&FeatureMatch> {
+        let on_contig = |m: &FeatureMatch| m.contig.full_name() == contig.full_name();
+
+        self.current = self
+            .matches
+            .iter()
+            .position(|m| on_contig(m) && m.start >= position)
+            .or_else(|| self.matches.iter().position(on_contig))
+            .unwrap_or(0);
+        self.matches.get(self.current)
+    }
+
+    /// Move k matches forward (negative: backward), wrapping around.
+    pub fn select_next(&mut self, k: isize) -> Option<&FeatureMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        self.current = (self.current as isize + k).rem_euclid(self.matches.len() as isize) as usize;
+        self.matches.get(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("gene_name=TP53", Ok(FeatureQuery { attribute: FeatureAttribute::GeneName, value: "TP53".to_string() }))]
+    #[case("strand = -", Ok(FeatureQuery { attribute: FeatureAttribute::Strand, value: "-".to_string() }))]
+    #[case("biotype=protein_coding", Err("Invalid find: biotype=protein_coding. Usage: find gene_name|transcript_id|strand=value".to_string()))]
+    #[case("gene_name=", Err("Invalid find: gene_name=. Usage: find gene_name|transcript_id|strand=value".to_string()))]
+    #[case("TP53", Err("Invalid find: TP53. Usage: find gene_name|transcript_id|strand=value".to_string()))]
+    fn test_parse_feature_query(
+        #[case] input: &str,
+        #[case] expected: Result<FeatureQuery, String>,
+    ) {
+        assert_eq!(FeatureQuery::parse(input), expected);
+    }
+
+    fn feature(contig: &str, start: usize, name: &str) -> FeatureMatch {
+        FeatureMatch {
+            contig: Contig::chrom(contig),
+            start,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_feature_matches() {
+        let query = FeatureQuery::parse("strand=+").unwrap();
+        let mut matches = FeatureMatches::new(
+            query,
+            vec![
+                feature("chr2", 500, "D"),
+                feature("chr1", 3000, "B"),
+                feature("chr1", 100, "A"),
+                feature("chr2", 100, "C"),
+            ],
+        );
+
+        let name = |m: Option<&FeatureMatch>| m.map(|m| m.name.clone());
+
+        assert_eq!(
+            name(matches.select_first(&Contig::chrom("chr1"), 1000)),
+            Some("B".to_string())
+        );
+        assert_eq!(name(matches.select_next(1)), Some("C".to_string()));
+        assert_eq!(name(matches.select_next(2)), Some("A".to_string()));
+        assert_eq!(name(matches.select_next(-1)), Some("D".to_string()));
+        assert_eq!(
+            name(matches.select_first(&Contig::chrom("chr2"), 9000)),
+            Some("C".to_string())
+        );
+        assert_eq!(
+            name(matches.select_first(&Contig::chrom("chr3"), 1)),
+            Some("A".to_string())
+        );
+        assert_eq!(matches.current_index(), 0);
+    }
+}
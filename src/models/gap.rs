@@ -0,0 +1,118 @@
+use crate::models::{contig::Contig, region::Region};
+
+/// Assembly gaps (runs of N in the reference) on one contig.
+pub struct GapIndex {
+    pub contig: Contig,
+
+    /// Sorted by start. 1-based, inclusive.
+    gaps: Vec<Region>,
+}
+
+impl GapIndex {
+    /// Gaps shorter than this are ignored.
+    pub const MIN_GAP_LENGTH: usize = 100;
+
+    pub fn new(contig: Contig, mut gaps: Vec<Region>) -> Self {
+        gaps.retain(|gap| gap.width() >= Self::MIN_GAP_LENGTH);
+        gaps.sort_by_key(|gap| gap.start);
+        Self { contig, gaps }
+    }
+
+    pub fn gaps(&self) -> &[Region] {
+        &self.gaps
+    }
+
+    /// The k-th gap starting after the position. k is 1-based.
+    pub fn get_k_gaps_after(&self, position: usize, k: usize) -> Option<&Region> {
+        if k == 0 {
+            return None;
+        }
+        let i_first = self.gaps.partition_point(|gap| gap.start <= position);
+        self.gaps.get(i_first + k - 1)
+    }
+
+    /// The k-th gap starting before the position. k is 1-based.
+    pub fn get_k_gaps_before(&self, position: usize, k: usize) -> Option<&Region> {
+        if k == 0 {
+            return None;
+        }
+        let n_before = self.gaps.partition_point(|gap| gap.start < position);
+        n_before.checked_sub(k).map(|i| &self.gaps[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn get_test_index() -> GapIndex {
+        let contig = Contig::chrom("chr1");
+        let gap = |start: usize, end: usize| Region {
+            contig: contig.clone(),
+            start,
+            end,
+        };
+        GapIndex::new(
+            contig.clone(),
+            vec![
+                gap(5001, 6000),
+                gap(1, 1000),
+                gap(3000, 3010), // too short
+                gap(9001, 9100),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_min_gap_length() {
+        let index = get_test_index();
+        assert_eq!(
+            index
+                .gaps()
+                .iter()
+                .map(|gap| gap.start)
+                .collect::<Vec<usize>>(),
+            vec![1, 5001, 9001]
+        );
+    }
+
+    #[rstest]
+    #[case(0, 1, Some(1))]
+    #[case(1, 1, Some(5001))]
+    #[case(3000, 1, Some(5001))]
+    #[case(3000, 2, Some(9001))]
+    #[case(3000, 3, None)]
+    #[case(9001, 1, None)]
+    #[case(3000, 0, None)]
+    fn test_get_k_gaps_after(
+        #[case] position: usize,
+        #[case] k: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        let index = get_test_index();
+        assert_eq!(
+            index.get_k_gaps_after(position, k).map(|gap| gap.start),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(1, 1, None)]
+    #[case(5001, 1, Some(1))]
+    #[case(10000, 1, Some(9001))]
+    #[case(10000, 3, Some(1))]
+    #[case(10000, 4, None)]
+    #[case(10000, 0, None)]
+    fn test_get_k_gaps_before(
+        #[case] position: usize,
+        #[case] k: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        let index = get_test_index();
+        assert_eq!(
+            index.get_k_gaps_before(position, k).map(|gap| gap.start),
+            expected
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use crate::models::contig::Contig;
+
+/// A persistent vertical guide line at a genome coordinate, drawn across all tracks.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Guide {
+    pub contig: Contig,
+
+    /// 1-based.
+    pub position: usize,
+
+    pub label: Option<String>,
+}
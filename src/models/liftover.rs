@@ -0,0 +1,163 @@
+use crate::error::TGVError;
+use std::collections::HashMap;
+
+/// An ungapped block of a chain, mapping target positions to query positions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct ChainBlock {
+    /// 0-based.
+    target_start: usize,
+    length: usize,
+
+    query_contig: String,
+
+    /// 0-based, on the query strand.
+    query_start: usize,
+    query_size: usize,
+
+    /// Whether the block maps to the reverse strand of the query.
+    reverse: bool,
+}
+
+/// Maps positions of the viewed build to another build, from a UCSC chain file.
+/// See: https://genome.ucsc.edu/goldenPath/help/chain.html
+pub struct Liftover {
+    /// By target contig name. Sorted by target start.
+    blocks: HashMap<String, Vec<ChainBlock>>,
+}
+
+impl Liftover {
+    pub fn from_chain_path(path: &str) -> Result<Self, TGVError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| TGVError::IOError(format!("Cannot read chain file {}: {}", path, e)))?;
+        Self::from_chain(&content)
+    }
+
+    pub fn from_chain(content: &str) -> Result<Self, TGVError> {
+        let mut blocks: HashMap<String, Vec<ChainBlock>> = HashMap::new();
+
+        // Pivots of the current chain: target contig, target and query positions.
+        let mut chain: Option<(String, usize, String, usize, usize, bool)> = None;
+
+        for (i_line, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid =
+                || TGVError::ParsingError(format!("Invalid chain line {}: {}", i_line + 1, line));
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let parse = |i: usize| -> Result<usize, TGVError> {
+                fields
+                    .get(i)
+                    .and_then(|field| field.parse::<usize>().ok())
+                    .ok_or_else(invalid)
+            };
+
+            if fields[0] == "chain" {
+                // chain score tName tSize tStrand tStart tEnd qName qSize qStrand qStart qEnd id
+                if fields.len() < 12 {
+                    return Err(invalid());
+                }
+                chain = Some((
+                    fields[2].to_string(),
+                    parse(5)?,
+                    fields[7].to_string(),
+                    parse(8)?,
+                    parse(10)?,
+                    fields[9] == "-",
+                ));
+                continue;
+            }
+
+            // size [dt dq]
+            let (target_contig, target_pivot, query_contig, query_size, query_pivot, reverse) =
+                chain.as_mut().ok_or_else(invalid)?;
+            let length = parse(0)?;
+            blocks
+                .entry(target_contig.clone())
+                .or_default()
+                .push(ChainBlock {
+                    target_start: *target_pivot,
+                    length,
+                    query_contig: query_contig.clone(),
+                    query_start: *query_pivot,
+                    query_size: *query_size,
+                    reverse: *reverse,
+                });
+
+            if fields.len() >= 3 {
+                *target_pivot += length + parse(1)?;
+                *query_pivot += length + parse(2)?;
+            } else {
+                chain = None; // Last block of the chain
+            }
+        }
+
+        for contig_blocks in blocks.values_mut() {
+            contig_blocks.sort_by_key(|block| block.target_start);
+        }
+
+        Ok(Self { blocks })
+    }
+
+    /// Query contig and 1-based position of a 1-based target position. None if the position is
+    /// not in an aligned block.
+    pub fn lift(&self, contig: &str, position: usize) -> Option<(&str, usize)> {
+        let blocks = self.blocks.get(contig)?;
+        let position = position.checked_sub(1)?; // 0-based
+
+        let i_after = blocks.partition_point(|block| block.target_start <= position);
+        let block = blocks.get(i_after.checked_sub(1)?)?;
+        if position >= block.target_start + block.length {
+            return None;
+        }
+
+        let query_position = block.query_start + position - block.target_start;
+        let query_position = if block.reverse {
+            block.query_size - query_position
+        } else {
+            query_position + 1
+        };
+        Some((block.query_contig.as_str(), query_position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const CHAIN: &str = "chain 1000 chr1 1000 + 100 130 chr1 2000 + 500 540 1\n\
+        10 5 15\n\
+        15\n\
+        \n\
+        chain 500 chr2 1000 + 0 10 chr3 100 - 20 30 2\n\
+        10\n";
+
+    #[rstest]
+    #[case("chr1", 100, None)]
+    #[case("chr1", 101, Some(("chr1", 501)))]
+    #[case("chr1", 110, Some(("chr1", 510)))]
+    #[case("chr1", 111, None)] // target gap
+    #[case("chr1", 116, Some(("chr1", 526)))]
+    #[case("chr1", 130, Some(("chr1", 540)))]
+    #[case("chr1", 131, None)]
+    #[case("chr2", 1, Some(("chr3", 80)))] // reverse strand
+    #[case("chr2", 10, Some(("chr3", 71)))]
+    #[case("chrX", 1, None)]
+    fn test_lift(
+        #[case] contig: &str,
+        #[case] position: usize,
+        #[case] expected: Option<(&str, usize)>,
+    ) {
+        let liftover = Liftover::from_chain(CHAIN).unwrap();
+        assert_eq!(liftover.lift(contig, position), expected);
+    }
+
+    #[test]
+    fn test_invalid_chain() {
+        assert!(Liftover::from_chain("10 5 15\n").is_err());
+        assert!(Liftover::from_chain("chain 1000 chr1 1000 +\n").is_err());
+    }
+}
@@ -1,5 +1,15 @@
 use crate::error::TGVError;
-use crate::models::{mode::InputMode, region::Region};
+use crate::models::{
+    alignment::{InsertionDisplay, Mapq255Meaning},
+    contig::Contig,
+    coverage::{CoverageAggregation, CoverageBaseline},
+    find::FeatureQuery,
+    mode::InputMode,
+    region::Region,
+    sequence::SequenceAnchor,
+    variant::VariantLocus,
+    view::{TrackKind, ViewSlot},
+};
 use strum::Display;
 /// State messages
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
@@ -22,15 +32,57 @@ pub enum StateMessage {
     GotoPreviousGenesStart(usize),
     GotoPreviousGenesEnd(usize),
 
+    GotoNextGap(usize),
+    GotoPreviousGap(usize),
+    ListGaps, // Report assembly gaps on the current contig.
+
+    SetTileStep(Option<usize>), // Step in bases. None: the width of the viewing window.
+    StepForward(usize),         // Move right by the tile step.
+    StepBackward(usize),        // Move left by the tile step.
+
     GotoNextContig(usize),
     GotoPreviousContig(usize),
 
     GoToGene(String),
 
+    GoToVariant(VariantLocus), // Center on the variant at base resolution.
+
+    FindFeatures(FeatureQuery), // Search annotation features and go to the first match.
+    GotoNextMatch(usize),
+    GotoPreviousMatch(usize),
+
     GoToDefault, // Calculate a default location based on the genome context
 
+    SaveView(ViewSlot),
+    RestoreView(ViewSlot),
+    ToggleView, // Switch to the other saved view.
+
+    AddGuide(Option<String>, usize, Option<String>), // contig (None: current contig), position, label
+    ClearGuides,
+
+    SetCoverageBaseline(CoverageBaseline),
+    SetCoverageAggregation(CoverageAggregation),
+
+    /// Whether soft-clipped bases are counted in coverage.
+    SetCoverageClips(bool),
+    SetSequenceAnchor(SequenceAnchor),
+    SetInsertionDisplay(InsertionDisplay),
+    ShowInsertions, // Show the sequences inserted after the middle of the view.
+    SetMinMapq(u8), // Reads with a lower MAPQ are hidden. 0: show all reads.
+    SetMapq255Meaning(Mapq255Meaning),
+    SetReadNames(bool), // Show read names before reads at base resolution.
+    CycleReadColoring,
+    QuickFilter(bool), // Filter reads by the base at the middle. true: carrying it, false: not.
+    ClearQuickFilter,
+    SetTrackDim(TrackKind, u8), // Dim level in percent. 0: not dimmed.
+
+    ExportPileup(String), // Write a text pileup of the viewing region to a file path.
+    YankSequence,         // Copy the reference sequence of the viewing region to the clipboard.
+    ExportCommandLine,    // Show and copy a tgv command line that reproduces the view.
+
     ZoomIn(usize),
     ZoomOut(usize),
+    FitRead, // Frame the topmost visible read at the middle of the view.
 
     SwitchMode(InputMode),
 
@@ -45,7 +97,18 @@ pub enum StateMessage {
     MoveCursorRight(usize),
     CommandModeRegisterError(String),
 
+    AddCharToPaletteRegister(char),
+    BackspacePaletteRegister,
+    ClearPaletteRegister,
+    SelectNextPaletteCommand,
+    SelectPreviousPaletteCommand,
+
     Error(TGVError),
+    ScrollErrorsBack(usize),    // By wrapped lines.
+    ScrollErrorsForward(usize), // By wrapped lines.
+
+    ShowHeader,
+    ScrollHeader(isize), // By lines. Negative: up.
 
     Quit,
 }
@@ -64,6 +127,10 @@ impl StateMessage {
                 | StateMessage::GotoPreviousGenesStart(_)
                 | StateMessage::GotoPreviousGenesEnd(_)
                 | StateMessage::GoToGene(_)
+                | StateMessage::GotoNextGap(_)
+                | StateMessage::GotoPreviousGap(_)
+                | StateMessage::ListGaps
+                | StateMessage::FindFeatures(_)
         )
     }
 }
@@ -75,4 +142,5 @@ pub enum DataMessage {
     RequiresCompleteAlignments(Region),
     RequiresCompleteFeatures(Region),
     RequiresCompleteSequences(Region),
+    RequiresGeneDensity(Contig), // Gene density over the whole contig.
 }
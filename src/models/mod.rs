@@ -1,9 +1,20 @@
+pub mod acceleration;
 pub mod alignment;
+pub mod consensus;
 pub mod contig;
+pub mod coverage;
 pub mod cytoband;
 pub mod data;
+pub mod density;
+pub mod find;
+pub mod gap;
+pub mod guide;
+pub mod liftover;
 pub mod message;
 pub mod mode;
+pub mod palette;
+pub mod pileup;
+pub mod primer;
 pub mod reference;
 pub mod region;
 pub mod register;
@@ -11,4 +22,6 @@ pub mod sequence;
 pub mod services;
 pub mod strand;
 pub mod track;
+pub mod variant;
+pub mod view;
 pub mod window;
@@ -5,4 +5,15 @@ pub enum InputMode {
     Normal,
     Command,
     Help,
+    Palette,
+
+    /// The SAM header overlay (:header).
+    Header,
+}
+
+impl InputMode {
+    /// Whether the mode covers the whole screen instead of the genome view.
+    pub fn is_full_screen(&self) -> bool {
+        matches!(self, InputMode::Help | InputMode::Header)
+    }
 }
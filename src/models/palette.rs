@@ -0,0 +1,352 @@
+use crate::models::{message::StateMessage, mode::InputMode, register::CommandModeRegister};
+use crossterm::event::KeyCode;
+
+/// A command listed in the command palette.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PaletteCommand {
+    /// Command as typed in command mode, without the leading ':'.
+    pub name: &'static str,
+
+    /// Argument syntax. None if the command takes no arguments.
+    pub arguments: Option<&'static str>,
+
+    pub description: &'static str,
+}
+
+impl PaletteCommand {
+    /// Console input to pre-fill when a command with arguments is selected.
+    pub fn prefill(&self) -> String {
+        format!("{} ", self.name)
+    }
+}
+
+/// All commands available in the command palette.
+pub const PALETTE_COMMANDS: &[PaletteCommand] = &[
+    PaletteCommand {
+        name: "q",
+        arguments: None,
+        description: "Quit",
+    },
+    PaletteCommand {
+        name: "h",
+        arguments: None,
+        description: "Help",
+    },
+    PaletteCommand {
+        name: "goto",
+        arguments: Some("<position | contig:position | gene | contig:position ref>alt>"),
+        description: "Go to a position, gene, or variant",
+    },
+    PaletteCommand {
+        name: "find",
+        arguments: Some("<gene_name|transcript_id|strand=value>"),
+        description: "Find genes by attribute (n / N for the next / previous match)",
+    },
+    PaletteCommand {
+        name: "view",
+        arguments: Some("<save a|b | a|b>"),
+        description: "Save or restore a view slot (Tab toggles between A and B)",
+    },
+    PaletteCommand {
+        name: "guide",
+        arguments: Some("<[contig:]position [label] | clear>"),
+        description: "Add or clear vertical guide lines",
+    },
+    PaletteCommand {
+        name: "gaps",
+        arguments: None,
+        description: "List assembly gaps on the contig (]N / [N to jump)",
+    },
+    PaletteCommand {
+        name: "cov",
+        arguments: Some("<agg mean|max|min | clips on|off>"),
+        description: "Choose how coverage is aggregated, and whether soft clips are counted",
+    },
+    PaletteCommand {
+        name: "set",
+        arguments: Some("<name> <value>"),
+        description: "Change a setting (coverage_baseline, coverage_bin_agg, sequence_anchor, \
+            insertions, min_mapq, mapq_255_meaning, read_names)",
+    },
+    PaletteCommand {
+        name: "track",
+        arguments: Some("<dim cytobands|coverage|reads|sequence|genes 0-100>"),
+        description: "Dim a track by a percentage to de-emphasize it",
+    },
+    PaletteCommand {
+        name: "step",
+        arguments: Some("[bases]"),
+        description: "Set a tiling step (default: visible width), then ]s / [s to step",
+    },
+    PaletteCommand {
+        name: "export-pileup",
+        arguments: Some("<path>"),
+        description: "Write a text pileup of the visible region",
+    },
+    PaletteCommand {
+        name: "yankseq",
+        arguments: None,
+        description: "Copy the reference sequence of the visible region (Y)",
+    },
+    PaletteCommand {
+        name: "export-cmd",
+        arguments: None,
+        description: "Show and copy a tgv command line that reproduces the view",
+    },
+    PaletteCommand {
+        name: "filter",
+        arguments: Some("clear"),
+        description: "Clear the quick filter of reads by base (f / F)",
+    },
+    PaletteCommand {
+        name: "header",
+        arguments: None,
+        description: "Show the SAM header (@SQ, @RG, @PG) of the BAM file",
+    },
+    PaletteCommand {
+        name: "fitread",
+        arguments: None,
+        description: "Zoom to fit the read at the middle of the view",
+    },
+    PaletteCommand {
+        name: "ins",
+        arguments: None,
+        description: "Show the bases inserted after the middle of the view",
+    },
+];
+
+/// Score a fuzzy match of the query against the candidate text.
+/// The query matches if its characters appear in order (case-insensitive) in the candidate.
+/// Lower scores are better: characters matched early and close together score lower.
+/// Returns None if the query does not match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<char>>();
+
+    let mut score = 0;
+    let mut i_candidate = 0;
+    let mut last_matched: Option<usize> = None;
+
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = candidate[i_candidate..].iter().position(|x| *x == c)?;
+        let i_matched = i_candidate + offset;
+
+        score += match last_matched {
+            Some(last_matched) => i_matched - last_matched - 1,
+            None => i_matched,
+        };
+
+        last_matched = Some(i_matched);
+        i_candidate = i_matched + 1;
+    }
+
+    Some(score)
+}
+
+pub struct PaletteRegister {
+    query: String,
+
+    /// Index of the selected command in matches().
+    selected: usize,
+}
+
+impl Default for PaletteRegister {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaletteRegister {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn query(&self) -> String {
+        self.query.clone()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn clear(&mut self) {
+        self.query = String::new();
+        self.selected = 0;
+    }
+
+    pub fn add_char(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self) {
+        let n_matches = self.matches().len();
+        if n_matches > 0 {
+            self.selected = (self.selected + 1) % n_matches;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        let n_matches = self.matches().len();
+        if n_matches > 0 {
+            self.selected = (self.selected + n_matches - 1) % n_matches;
+        }
+    }
+
+    /// Commands matching the query, best match first.
+    /// Names are matched before descriptions.
+    pub fn matches(&self) -> Vec<&'static PaletteCommand> {
+        let mut scored = PALETTE_COMMANDS
+            .iter()
+            .filter_map(|command| {
+                fuzzy_score(&self.query, command.name)
+                    .or_else(|| {
+                        fuzzy_score(&self.query, command.description)
+                            .map(|score| score + command.name.len() + 1)
+                    })
+                    .map(|score| (score, command))
+            })
+            .collect::<Vec<(usize, &PaletteCommand)>>();
+
+        scored.sort_by_key(|(score, _)| *score); // stable: ties keep the registry order
+        scored.into_iter().map(|(_, command)| command).collect()
+    }
+
+    pub fn selected_command(&self) -> Option<&'static PaletteCommand> {
+        self.matches().get(self.selected).copied()
+    }
+}
+
+impl PaletteRegister {
+    pub fn translate(&self, c: KeyCode) -> Result<Vec<StateMessage>, String> {
+        match c {
+            KeyCode::Esc => Ok(vec![
+                StateMessage::ClearPaletteRegister,
+                StateMessage::SwitchMode(InputMode::Normal),
+            ]),
+            KeyCode::Enter => {
+                let command = match self.selected_command() {
+                    Some(command) => command,
+                    None => return Err(format!("No command matches: {}", self.query)),
+                };
+
+                match command.arguments {
+                    // Let the user fill in the arguments in command mode.
+                    Some(_) => {
+                        let mut messages = vec![
+                            StateMessage::ClearPaletteRegister,
+                            StateMessage::ClearCommandModeRegisters,
+                            StateMessage::SwitchMode(InputMode::Command),
+                        ];
+                        messages.extend(
+                            command
+                                .prefill()
+                                .chars()
+                                .map(StateMessage::AddCharToCommandModeRegisters),
+                        );
+                        Ok(messages)
+                    }
+                    None => {
+                        let mut messages = vec![
+                            StateMessage::ClearPaletteRegister,
+                            StateMessage::SwitchMode(InputMode::Normal),
+                        ];
+                        messages.extend(CommandModeRegister::parse_input(command.name)?);
+                        Ok(messages)
+                    }
+                }
+            }
+            KeyCode::Up => Ok(vec![StateMessage::SelectPreviousPaletteCommand]),
+            KeyCode::Down | KeyCode::Tab => Ok(vec![StateMessage::SelectNextPaletteCommand]),
+            KeyCode::Backspace => Ok(vec![StateMessage::BackspacePaletteRegister]),
+            KeyCode::Char(c) => Ok(vec![StateMessage::AddCharToPaletteRegister(c)]),
+            _ => Err("Invalid input".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "goto", Some(0))]
+    #[case("goto", "goto", Some(0))]
+    #[case("gt", "goto", Some(1))]
+    #[case("GO", "goto", Some(0))]
+    #[case("oo", "goto", Some(2))]
+    #[case("og", "goto", None)]
+    #[case("gotox", "goto", None)]
+    #[case("go gene", "Go to a gene", Some(6))]
+    fn test_fuzzy_score(
+        #[case] query: &str,
+        #[case] candidate: &str,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(fuzzy_score(query, candidate), expected);
+    }
+
+    #[rstest]
+    #[case("", vec![
+        "q", "h", "goto", "find", "view", "guide", "gaps", "cov", "set", "track", "step",
+        "export-pileup", "yankseq", "export-cmd", "filter", "header", "fitread", "ins",
+    ])]
+    #[case("g", vec![
+        "goto", "guide", "gaps", "set", "find", "step", "cov", "export-cmd", "track",
+        "header", "view", "export-pileup", "yankseq",
+    ])]
+    #[case("help", vec!["h", "cov", "set"])]
+    #[case("variant", vec!["goto", "cov", "set"])]
+    #[case("pileup", vec!["export-pileup"])]
+    #[case("xyz", vec![])]
+    fn test_palette_matches(#[case] query: &str, #[case] expected: Vec<&str>) {
+        let register = PaletteRegister {
+            query: query.to_string(),
+            selected: 0,
+        };
+        assert_eq!(
+            register
+                .matches()
+                .iter()
+                .map(|command| command.name)
+                .collect::<Vec<&str>>(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("q", Ok(vec![
+        StateMessage::ClearPaletteRegister,
+        StateMessage::SwitchMode(InputMode::Normal),
+        StateMessage::Quit,
+    ]))]
+    #[case("goto", Ok(vec![
+        StateMessage::ClearPaletteRegister,
+        StateMessage::ClearCommandModeRegisters,
+        StateMessage::SwitchMode(InputMode::Command),
+        StateMessage::AddCharToCommandModeRegisters('g'),
+        StateMessage::AddCharToCommandModeRegisters('o'),
+        StateMessage::AddCharToCommandModeRegisters('t'),
+        StateMessage::AddCharToCommandModeRegisters('o'),
+        StateMessage::AddCharToCommandModeRegisters(' '),
+    ]))]
+    #[case("xyz", Err("No command matches: xyz".to_string()))]
+    fn test_palette_enter(
+        #[case] query: &str,
+        #[case] expected: Result<Vec<StateMessage>, String>,
+    ) {
+        let register = PaletteRegister {
+            query: query.to_string(),
+            selected: 0,
+        };
+        assert_eq!(register.translate(KeyCode::Enter), expected);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::models::{
+    alignment::{AlignedRead, Alignment, ReadFilter},
+    region::Region,
+    sequence::base_matches_reference,
+};
+use rust_htslib::bam::record::Cigar;
+
+/// Plain-text pileup of reads over a region, one read per line beneath the reference.
+/// Reads not admitted by the filter are left out.
+/// Columns are genome positions, independent of the terminal width.
+///
+/// Legend:
+/// - `.`: match to the reference
+/// - `ACGTN`: mismatch, or any base when the reference is not available
+/// - `acgtn`: soft-clipped base
+/// - `-`: deletion
+/// - `~`: skipped region (e.g. intron)
+///
+/// Insertions do not occupy columns. They are listed after each read as `ins@pos:bases`, where
+/// pos is the reference position before the insertion.
+pub fn format_pileup(
+    alignment: &Alignment,
+    region: &Region,
+    reference: Option<&str>,
+    filter: &ReadFilter,
+) -> String {
+    let mut reads = alignment
+        .reads
+        .iter()
+        .filter(|read| {
+            read.start.saturating_sub(read.leading_softclips) <= region.end
+                && read.end.saturating_add(read.trailing_softclips) >= region.start
+        })
+        .filter(|read| filter.admits(&read.read))
+        .collect::<Vec<&AlignedRead>>();
+    reads.sort_by_key(|read| (read.start, read.y));
+
+    let mut lines = vec![
+        format!("# {}", region),
+        "# .: match  ACGT: mismatch  acgt: softclip  -: deletion  ~: skipped  ins@pos: insertion after pos".to_string(),
+    ];
+
+    lines.push(match reference {
+        Some(reference) => format!("{}  reference", reference.to_uppercase()),
+        None => format!("{}  reference (not available)", "N".repeat(region.width())),
+    });
+
+    for read in reads {
+        let (row, insertions) = format_read(read, region, reference);
+
+        let mut line = format!("{}  {}", row, String::from_utf8_lossy(read.read.qname()));
+        for (position, bases) in insertions {
+            line += &format!("  ins@{}:{}", position, bases);
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Format one read over the region. Returns the row and insertions within the region.
+fn format_read(
+    read: &AlignedRead,
+    region: &Region,
+    reference: Option<&str>,
+) -> (String, Vec<(usize, String)>) {
+    let mut row = vec![' '; region.width()];
+    let mut insertions = Vec::new();
+
+    let mut set = |position: usize, c: char| {
+        if position >= region.start && position <= region.end {
+            row[position - region.start] = c;
+        }
+    };
+
+    let reference = reference.map(|reference| reference.as_bytes());
+    let reference_base_at = |position: usize| -> Option<u8> {
+        reference?.get(position.checked_sub(region.start)?).copied()
+    };
+
+    let sequence = read.read.seq().as_bytes();
+    let mut reference_pivot = read.start; // 1-based
+    let mut query_pivot = 0;
+
+    for op in read.read.cigar().iter() {
+        let length = op.len() as usize;
+        match op {
+            Cigar::SoftClip(_) => {
+                // Leading softclips end right before the aligned start.
+                let clip_start = if query_pivot == 0 {
+                    reference_pivot as isize - length as isize
+                } else {
+                    reference_pivot as isize
+                };
+                for i in 0..length {
+                    let position = clip_start + i as isize;
+                    if let (true, Some(base)) = (position >= 1, sequence.get(query_pivot + i)) {
+                        set(position as usize, (*base as char).to_ascii_lowercase());
+                    }
+                }
+                query_pivot += length;
+            }
+            Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) => {
+                for i in 0..length {
+                    // Reads without a stored sequence (SEQ *) are drawn with N bases.
+                    let base = sequence
+                        .get(query_pivot + i)
+                        .copied()
+                        .unwrap_or(b'N')
+                        .to_ascii_uppercase();
+                    let position = reference_pivot + i;
+                    let c = match reference_base_at(position) {
+                        // Keep N read bases visible in the text.
+                        Some(reference_base)
+                            if base != b'N' && base_matches_reference(base, reference_base) =>
+                        {
+                            '.'
+                        }
+                        _ => base as char,
+                    };
+                    set(position, c);
+                }
+                reference_pivot += length;
+                query_pivot += length;
+            }
+            Cigar::Ins(_) => {
+                let position = reference_pivot - 1;
+                if position >= region.start && position <= region.end {
+                    let bases = match sequence.get(query_pivot..query_pivot + length) {
+                        Some(bases) => String::from_utf8_lossy(bases).to_uppercase(),
+                        None => "N".repeat(length),
+                    };
+                    insertions.push((position, bases));
+                }
+                query_pivot += length;
+            }
+            Cigar::Del(_) | Cigar::RefSkip(_) => {
+                let c = if matches!(op, Cigar::Del(_)) {
+                    '-'
+                } else {
+                    '~'
+                };
+                for i in 0..length {
+                    set(reference_pivot + i, c);
+                }
+                reference_pivot += length;
+            }
+            Cigar::HardClip(_) | Cigar::Pad(_) => {}
+        }
+    }
+
+    (row.into_iter().collect(), insertions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{alignment::ClipLengths, contig::Contig};
+    use rust_htslib::bam::record::{CigarString, Record};
+
+    fn get_test_read(start: usize, cigar: Vec<Cigar>, sequence: &[u8]) -> AlignedRead {
+        let mut record = Record::new();
+        let cigar = CigarString(cigar);
+        record.set(b"read1", Some(&cigar), sequence, &vec![30; sequence.len()]);
+        record.set_pos(start as i64 - 1);
+
+        let cigar = record.cigar();
+        let clips = ClipLengths::from_cigar(&cigar);
+        AlignedRead {
+            leading_softclips: clips.leading_softclips,
+            trailing_softclips: clips.trailing_softclips,
+            leading_hardclips: clips.leading_hardclips,
+            trailing_hardclips: clips.trailing_hardclips,
+            end: cigar.end_pos() as usize,
+            read: record,
+            start,
+            y: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_read() {
+        let region = Region {
+            contig: Contig::chrom("chr1"),
+            start: 1,
+            end: 14,
+        };
+        let reference = "AAAACCCCGGGGTT";
+
+        // 2S4M1I2M1D2M: softclip at 3-4, matches at 5-8 (mismatch at 7), insertion after 8,
+        // matches at 9-10, deletion at 11, matches at 12-13.
+        let read = get_test_read(
+            5,
+            vec![
+                Cigar::SoftClip(2),
+                Cigar::Match(4),
+                Cigar::Ins(1),
+                Cigar::Match(2),
+                Cigar::Del(1),
+                Cigar::Match(2),
+            ],
+            b"TTCCACAGGGT",
+        );
+
+        let (row, insertions) = format_read(&read, &region, Some(reference));
+        assert_eq!(row, "  tt..A...-.. ");
+        assert_eq!(insertions, vec![(8, "A".to_string())]);
+
+        let (row, _) = format_read(&read, &region, None);
+        assert_eq!(row, "  ttCCACGG-GT ");
+    }
+
+    #[test]
+    fn test_format_read_without_sequence() {
+        let region = Region {
+            contig: Contig::chrom("chr1"),
+            start: 1,
+            end: 14,
+        };
+
+        // The read of test_format_read with SEQ *.
+        let read = get_test_read(
+            5,
+            vec![
+                Cigar::SoftClip(2),
+                Cigar::Match(4),
+                Cigar::Ins(1),
+                Cigar::Match(2),
+                Cigar::Del(1),
+                Cigar::Match(2),
+            ],
+            b"",
+        );
+
+        let (row, insertions) = format_read(&read, &region, Some("AAAACCCCGGGGTT"));
+        assert_eq!(row, "    NNNNNN-NN ");
+        assert_eq!(insertions, vec![(8, "N".to_string())]);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::TGVError;
+use crate::models::{contig::Contig, reference::Reference};
+
+/// A primer footprint from a primer BED file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Primer {
+    pub contig: Contig,
+
+    /// 1-based, inclusive.
+    pub start: usize,
+
+    /// 1-based, inclusive.
+    pub end: usize,
+
+    pub name: Option<String>,
+}
+
+impl Primer {
+    pub fn from_bed_path(path: &str, reference: Option<&Reference>) -> Result<Vec<Self>, TGVError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| TGVError::IOError(format!("Cannot read primer BED {}: {}", path, e)))?;
+        Self::from_bed(&content, reference)
+    }
+
+    /// Parse BED records: chrom, chromStart, chromEnd, and an optional name.
+    /// BED coordinates are 0-based, half-open. Header, track, and browser lines are skipped.
+    pub fn from_bed(content: &str, reference: Option<&Reference>) -> Result<Vec<Self>, TGVError> {
+        let mut primers = Vec::new();
+
+        for (i_line, line) in content.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("track")
+                || line.starts_with("browser")
+            {
+                continue;
+            }
+
+            let invalid =
+                || TGVError::ParsingError(format!("Invalid BED line {}: {}", i_line + 1, line));
+
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            if fields.len() < 3 {
+                return Err(invalid());
+            }
+            let start = fields[1].parse::<usize>().map_err(|_| invalid())?;
+            let end = fields[2].parse::<usize>().map_err(|_| invalid())?;
+            if end <= start {
+                return Err(invalid());
+            }
+
+            primers.push(Primer {
+                contig: match reference {
+                    Some(Reference::Hg38) | Some(Reference::Hg19) => Contig::chrom(fields[0]),
+                    _ => Contig::contig(fields[0]),
+                },
+                start: start + 1,
+                end,
+                name: fields.get(3).map(|name| name.to_string()),
+            });
+        }
+
+        Ok(primers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primers_from_bed() {
+        let content = "track name=primers\n\
+            # comment\n\
+            MN908947.3\t30\t54\tnCoV-2019_1_LEFT\n\
+            MN908947.3\t385\t410\n";
+
+        assert_eq!(
+            Primer::from_bed(content, None),
+            Ok(vec![
+                Primer {
+                    contig: Contig::contig("MN908947.3"),
+                    start: 31,
+                    end: 54,
+                    name: Some("nCoV-2019_1_LEFT".to_string()),
+                },
+                Primer {
+                    contig: Contig::contig("MN908947.3"),
+                    start: 386,
+                    end: 410,
+                    name: None,
+                },
+            ])
+        );
+
+        assert_eq!(
+            Primer::from_bed("17\t100\t120\n", Some(&Reference::Hg38)).map(|p| p[0].contig.clone()),
+            Ok(Contig::chrom("chr17"))
+        );
+        assert!(Primer::from_bed("chr1\t120\t100\n", None).is_err());
+        assert!(Primer::from_bed("chr1 100 120\n", None).is_err());
+    }
+}
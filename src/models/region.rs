@@ -49,4 +49,77 @@ impl Region {
     pub fn width(&self) -> usize {
         self.length()
     }
+
+    /// The region widened by ratio * width / 2 on each side, within [1, contig_length].
+    pub fn expand(&self, ratio: usize, contig_length: Option<usize>) -> Self {
+        Self {
+            contig: self.contig.clone(),
+            start: self.start.saturating_sub(ratio * self.width() / 2).max(1),
+            end: self
+                .end
+                .saturating_add(ratio * self.width() / 2)
+                .min(contig_length.unwrap_or(usize::MAX)),
+        }
+    }
+
+    /// The part of the region within [1, contig_length]. None if the region starts after the
+    /// contig end.
+    pub fn clamp(&self, contig_length: Option<usize>) -> Option<Self> {
+        let end = self.end.min(contig_length.unwrap_or(usize::MAX));
+        Self::new(self.contig.clone(), self.start, end).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn region(start: usize, end: usize) -> Region {
+        Region::new(Contig::contig("chr1"), start, end).unwrap()
+    }
+
+    #[rstest]
+    #[case(region(100, 109), 3, None, region(85, 124))]
+    #[case(region(10, 19), 3, Some(120), region(1, 34))]
+    #[case(region(100, 109), 3, Some(120), region(85, 120))]
+    #[case(region(100, 109), 0, Some(120), region(100, 109))]
+    fn test_expand(
+        #[case] region: Region,
+        #[case] ratio: usize,
+        #[case] contig_length: Option<usize>,
+        #[case] expected: Region,
+    ) {
+        assert_eq!(region.expand(ratio, contig_length), expected);
+    }
+
+    #[rstest]
+    #[case(region(100, 109), None, Some(region(100, 109)))]
+    #[case(region(100, 109), Some(105), Some(region(100, 105)))]
+    #[case(region(100, 109), Some(100), Some(region(100, 100)))]
+    #[case(region(100, 109), Some(99), None)]
+    fn test_clamp(
+        #[case] region: Region,
+        #[case] contig_length: Option<usize>,
+        #[case] expected: Option<Region>,
+    ) {
+        assert_eq!(region.clamp(contig_length), expected);
+    }
+
+    /// The BAM header contig is longer than the reference contig. Reads are cached up to the BAM
+    /// length, and sequences only up to the reference length.
+    #[test]
+    fn test_cache_regions_beyond_reference_end() {
+        let (bam_length, reference_length) = (Some(1000), Some(900));
+        let viewing_region = region(880, 919);
+
+        assert_eq!(viewing_region.expand(3, bam_length), region(820, 979));
+        assert_eq!(
+            viewing_region
+                .clamp(reference_length)
+                .map(|region| region.expand(3, reference_length)),
+            Some(region(849, 900))
+        );
+        assert_eq!(region(950, 989).clamp(reference_length), None);
+    }
 }
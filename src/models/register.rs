@@ -1,4 +1,13 @@
-use crate::models::{message::StateMessage, mode::InputMode};
+use crate::models::{
+    alignment::{InsertionDisplay, Mapq255Meaning},
+    coverage::CoverageAggregation,
+    find::FeatureQuery,
+    message::StateMessage,
+    mode::InputMode,
+    variant::VariantLocus,
+    view::{TrackKind, ViewSlot},
+};
+use clap::ValueEnum;
 use crossterm::event::KeyCode;
 
 #[derive(Clone)]
@@ -30,9 +39,15 @@ impl NormalModeRegister {
 
     const ZOOM_STEP: usize = 2;
 
-    const VALID_MOVEMENT_SUFFIXES: [&str; 18] = [
+    const VALID_MOVEMENT_SUFFIXES: [&str; 30] = [
+        "[e", // scroll errors back. Before "e".
+        "]e", // scroll errors forward. Before "e".
         "ge", // previous exon end
         "gE", // previous exon start,g1
+        "]N", // next assembly gap
+        "[N", // previous assembly gap
+        "]s", // next tile
+        "[s", // previous tile
         "w",  // next exon start
         "b",  // previous exon start
         "e",  // next exon end
@@ -49,6 +64,12 @@ impl NormalModeRegister {
         "o",  // zoom in
         "{",  // previous contig
         "}",  // next contig
+        "n",  // next :find match
+        "N",  // previous :find match. After "]N" and "[N".
+        "Y",  // copy the visible reference sequence
+        "C",  // cycle read coloring
+        "f",  // show only reads with the base at the middle
+        "F",  // hide reads with the base at the middle
     ];
 
     /// Translate key input to a state message. This does not mute states. States are muted downstream by handling state messages.
@@ -84,9 +105,10 @@ impl NormalModeRegister {
                 }
             },
 
-            KeyCode::Char('g') => {
+            // Prefixes of two-key commands
+            KeyCode::Char(c @ ('g' | '[' | ']')) => {
                 if self.input.is_empty() || self.input.parse::<usize>().is_ok() {
-                    Ok(vec![StateMessage::AddCharToNormalModeRegisters('g')])
+                    Ok(vec![StateMessage::AddCharToNormalModeRegisters(c)])
                 } else {
                     Err(format!("Invalid input: {}", self.input))
                 }
@@ -130,6 +152,30 @@ impl NormalModeRegister {
                         StateMessage::GotoPreviousGenesEnd(n_movements),
                         StateMessage::ClearNormalModeRegisters,
                     ]),
+                    "[e" => Ok(vec![
+                        StateMessage::ScrollErrorsBack(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "]e" => Ok(vec![
+                        StateMessage::ScrollErrorsForward(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "]N" => Ok(vec![
+                        StateMessage::GotoNextGap(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "[N" => Ok(vec![
+                        StateMessage::GotoPreviousGap(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "]s" => Ok(vec![
+                        StateMessage::StepForward(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "[s" => Ok(vec![
+                        StateMessage::StepBackward(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
                     "w" => Ok(vec![
                         StateMessage::GotoNextExonsStart(n_movements),
                         StateMessage::ClearNormalModeRegisters,
@@ -197,6 +243,30 @@ impl NormalModeRegister {
                         StateMessage::GotoNextContig(n_movements),
                         StateMessage::ClearNormalModeRegisters,
                     ]),
+                    "n" => Ok(vec![
+                        StateMessage::GotoNextMatch(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "N" => Ok(vec![
+                        StateMessage::GotoPreviousMatch(n_movements),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "Y" => Ok(vec![
+                        StateMessage::YankSequence,
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "C" => Ok(vec![
+                        StateMessage::CycleReadColoring,
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "f" => Ok(vec![
+                        StateMessage::QuickFilter(true),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
+                    "F" => Ok(vec![
+                        StateMessage::QuickFilter(false),
+                        StateMessage::ClearNormalModeRegisters,
+                    ]),
                     _ => Err(format!("Invalid normal mode input: {}", string)),
                 }
             }
@@ -277,16 +347,148 @@ impl CommandModeRegister {
     /// :h: Help.
     /// :1234: Go to position 1234 on the same contig.
     /// :12:1234: Go to position 1234 on contig 12.
+    /// :7:140753336 A>T: Go to a variant at base resolution.
+    /// :goto _region_: Same as :_region_.
+    /// :gaps: List assembly gaps on the current contig.
+    /// :find _attribute_=_value_: Go to the first matching gene on the contig. n / N cycle.
+    /// :cov agg mean|max|min: How coverage is aggregated when multiple bases share a column.
+    /// :cov clips on|off: Whether soft-clipped bases are counted in coverage.
+    /// :set _name_ _value_: Change a setting: coverage_baseline, coverage_bin_agg, sequence_anchor,
+    /// insertions, min_mapq, mapq_255_meaning, read_names (on|off).
+    /// :export-pileup _path_: Write a text pileup of the visible region to a file.
+    /// :yankseq: Copy the reference sequence of the visible region to the clipboard (also Y).
+    /// :export-cmd: Show and copy a tgv command line (and commands) that reproduce the view.
+    /// :header: Show the SAM header of the BAM file.
+    /// :ins: Show the sequences inserted after the middle of the view.
+    /// :filter clear: Clear the quick filter set with f / F.
+    /// :track dim _track_ _level_: Dim a track (cytobands, coverage, reads, sequence, genes) by a
+    /// percentage. 0 restores it.
+    /// :step [_n_]: Step by n bases (default: the visible width) with ]s / [s.
+    /// :guide [_contig_:]_pos_ [_label_]: Add a vertical guide line.
+    /// :guide clear: Remove all guide lines.
+    /// :view save a|b: Save the current view to slot A or B.
+    /// :view a|b: Restore the view in slot A or B.
     pub fn parse(&self) -> Result<Vec<StateMessage>, String> {
-        if self.input == "q" {
+        Self::parse_input(&self.input)
+    }
+
+    /// Parse text pasted in normal mode. Only a region, gene, or variant is accepted, so that
+    /// pasted text never runs a command.
+    pub fn parse_paste(input: &str) -> Result<Vec<StateMessage>, String> {
+        let error = || format!("Cannot navigate to pasted text: {}", input.trim());
+        let messages = Self::parse_input(input).map_err(|_| error())?;
+        if messages.iter().all(|message| {
+            matches!(
+                message,
+                StateMessage::GotoCoordinate(_)
+                    | StateMessage::GotoContigCoordinate(_, _)
+                    | StateMessage::GoToGene(_)
+                    | StateMessage::GoToVariant(_)
+            )
+        }) {
+            Ok(messages)
+        } else {
+            Err(error())
+        }
+    }
+
+    pub fn parse_input(input: &str) -> Result<Vec<StateMessage>, String> {
+        let input = input.trim();
+
+        if input == "q" {
             return Ok(vec![StateMessage::Quit]);
         }
 
-        if self.input == "h" {
+        if input == "h" {
             return Ok(vec![StateMessage::SwitchMode(InputMode::Help)]);
         }
 
-        let split = self.input.split(":").collect::<Vec<&str>>();
+        if input == "yankseq" {
+            return Ok(vec![StateMessage::YankSequence]);
+        }
+
+        if input == "gaps" {
+            return Ok(vec![StateMessage::ListGaps]);
+        }
+
+        if input == "export-cmd" {
+            return Ok(vec![StateMessage::ExportCommandLine]);
+        }
+
+        if input == "header" {
+            return Ok(vec![StateMessage::ShowHeader]);
+        }
+
+        if input == "fitread" {
+            return Ok(vec![StateMessage::FitRead]);
+        }
+
+        if input == "ins" {
+            return Ok(vec![StateMessage::ShowInsertions]);
+        }
+
+        let (command, argument) = input.split_once(' ').unwrap_or((input, ""));
+
+        if command == "filter" {
+            return match argument.trim() {
+                "clear" => Ok(vec![StateMessage::ClearQuickFilter]),
+                _ => Err("Usage: filter clear".to_string()),
+            };
+        }
+
+        if command == "view" {
+            return Self::parse_view(argument.trim());
+        }
+
+        if command == "guide" {
+            return Self::parse_guide(argument.trim());
+        }
+
+        if command == "cov" {
+            return Self::parse_cov(argument.trim());
+        }
+
+        if command == "set" {
+            return Self::parse_set(argument.trim());
+        }
+
+        if command == "track" {
+            return Self::parse_track(argument.trim());
+        }
+
+        if command == "find" {
+            return Ok(vec![StateMessage::FindFeatures(FeatureQuery::parse(
+                argument.trim(),
+            )?)]);
+        }
+
+        if command == "step" {
+            return match argument.trim() {
+                "" => Ok(vec![StateMessage::SetTileStep(None)]),
+                step => match step.parse::<usize>() {
+                    Ok(step) if step > 0 => Ok(vec![StateMessage::SetTileStep(Some(step))]),
+                    _ => Err(format!("Invalid step: {}. Usage: step [bases]", step)),
+                },
+            };
+        }
+
+        if command == "export-pileup" {
+            let path = argument.trim();
+            if path.is_empty() {
+                return Err("Usage: export-pileup <path>".to_string());
+            }
+            return Ok(vec![StateMessage::ExportPileup(path.to_string())]);
+        }
+
+        if let Some(region) = input.strip_prefix("goto ") {
+            return Self::parse_input(region);
+        }
+
+        if let Some(variant) = VariantLocus::parse(input) {
+            return Ok(vec![StateMessage::GoToVariant(variant)]);
+        }
+
+        let split = input.split(":").collect::<Vec<&str>>();
 
         match split.len() {
             1 => match split[0].parse::<usize>() {
@@ -298,9 +500,149 @@ impl CommandModeRegister {
                     split[0].to_string(),
                     n,
                 )]),
-                Err(_) => Err(format!("Invalid command mode input: {}", self.input)),
+                Err(_) => Err(format!("Invalid command mode input: {}", input)),
             },
-            _ => Err(format!("Invalid command mode input: {}", self.input)),
+            _ => Err(format!("Invalid command mode input: {}", input)),
+        }
+    }
+}
+
+/// Argument parsing
+impl CommandModeRegister {
+    /// Parse "save a|b" or "a|b".
+    fn parse_view(argument: &str) -> Result<Vec<StateMessage>, String> {
+        let usage = || {
+            format!(
+                "Invalid view: {}. Usage: view save a|b | view a|b",
+                argument
+            )
+        };
+
+        match argument.split_once(' ') {
+            Some(("save", slot)) => match ViewSlot::parse(slot) {
+                Some(slot) => Ok(vec![StateMessage::SaveView(slot)]),
+                None => Err(usage()),
+            },
+            None => match ViewSlot::parse(argument) {
+                Some(slot) => Ok(vec![StateMessage::RestoreView(slot)]),
+                None => Err(usage()),
+            },
+            _ => Err(usage()),
+        }
+    }
+
+    /// Parse "agg mean|max|min".
+    fn parse_cov(argument: &str) -> Result<Vec<StateMessage>, String> {
+        let usage = || {
+            format!(
+                "Invalid cov: {}. Usage: cov agg mean|max|min | cov clips on|off",
+                argument
+            )
+        };
+
+        match argument.split_once(' ') {
+            Some(("agg", aggregation)) => {
+                match CoverageAggregation::from_str(aggregation.trim(), true) {
+                    Ok(aggregation) => Ok(vec![StateMessage::SetCoverageAggregation(aggregation)]),
+                    Err(_) => Err(usage()),
+                }
+            }
+            Some(("clips", toggle)) => match toggle.trim() {
+                "on" => Ok(vec![StateMessage::SetCoverageClips(true)]),
+                "off" => Ok(vec![StateMessage::SetCoverageClips(false)]),
+                _ => Err(usage()),
+            },
+            _ => Err(usage()),
+        }
+    }
+
+    /// Parse "dim track level".
+    fn parse_track(argument: &str) -> Result<Vec<StateMessage>, String> {
+        let usage = || {
+            format!(
+                "Invalid track: {}. Usage: track dim cytobands|coverage|reads|sequence|genes 0-100",
+                argument
+            )
+        };
+
+        match argument
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .as_slice()
+        {
+            ["dim", track, level] => {
+                let track = TrackKind::from_str(track, true).map_err(|_| usage())?;
+                match level.trim_end_matches('%').parse::<u8>() {
+                    Ok(level) if level <= 100 => Ok(vec![StateMessage::SetTrackDim(track, level)]),
+                    _ => Err(usage()),
+                }
+            }
+            _ => Err(usage()),
+        }
+    }
+
+    /// Parse "name value". Names can be written with '-' or '_'.
+    fn parse_set(argument: &str) -> Result<Vec<StateMessage>, String> {
+        let (name, value) = match argument.split_once(' ') {
+            Some((name, value)) => (name.replace('-', "_"), value.trim()),
+            None => return Err("Usage: set <name> <value>".to_string()),
+        };
+        let invalid = |_| format!("Invalid value for {}: {}", name, value);
+
+        match name.as_str() {
+            "coverage_baseline" => CoverageBaseline::from_str(value, true)
+                .map(|baseline| vec![StateMessage::SetCoverageBaseline(baseline)])
+                .map_err(invalid),
+            "coverage_bin_agg" => CoverageAggregation::from_str(value, true)
+                .map(|aggregation| vec![StateMessage::SetCoverageAggregation(aggregation)])
+                .map_err(invalid),
+            "sequence_anchor" => SequenceAnchor::from_str(value, true)
+                .map(|anchor| vec![StateMessage::SetSequenceAnchor(anchor)])
+                .map_err(invalid),
+            "insertions" => InsertionDisplay::from_str(value, true)
+                .map(|insertions| vec![StateMessage::SetInsertionDisplay(insertions)])
+                .map_err(invalid),
+            "min_mapq" => value
+                .parse::<u8>()
+                .map(|min_mapq| vec![StateMessage::SetMinMapq(min_mapq)])
+                .map_err(|e| invalid(e.to_string())),
+            "mapq_255_meaning" => Mapq255Meaning::from_str(value, true)
+                .map(|meaning| vec![StateMessage::SetMapq255Meaning(meaning)])
+                .map_err(invalid),
+            "read_names" => match value {
+                "on" => Ok(vec![StateMessage::SetReadNames(true)]),
+                "off" => Ok(vec![StateMessage::SetReadNames(false)]),
+                _ => Err(invalid(String::new())),
+            },
+            _ => Err(format!("Unknown setting: {}", name)),
+        }
+    }
+
+    /// Parse "[contig:]position [label]" or "clear".
+    fn parse_guide(argument: &str) -> Result<Vec<StateMessage>, String> {
+        if argument == "clear" {
+            return Ok(vec![StateMessage::ClearGuides]);
+        }
+
+        let (locus, label) = argument.split_once(' ').unwrap_or((argument, ""));
+        let label = match label.trim() {
+            "" => None,
+            label => Some(label.to_string()),
+        };
+
+        let (contig, position) = match locus.rsplit_once(':') {
+            Some((contig, position)) => (Some(contig.to_string()), position),
+            None => (None, locus),
+        };
+
+        match position.parse::<usize>() {
+            Ok(position) if position > 0 => {
+                Ok(vec![StateMessage::AddGuide(contig, position, label)])
+            }
+            _ => Err(format!(
+                "Invalid guide: {}. Usage: guide [contig:]position [label] | guide clear",
+                argument
+            )),
         }
     }
 }
@@ -308,17 +650,74 @@ impl CommandModeRegister {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::find::FeatureAttribute;
     use crate::models::message::StateMessage;
     use rstest::rstest;
 
     #[rstest]
     #[case("q", Ok(vec![StateMessage::Quit]))]
     #[case("1234", Ok(vec![StateMessage::GotoCoordinate(1234)]))]
+    #[case("yankseq", Ok(vec![StateMessage::YankSequence]))]
+    #[case("export-cmd", Ok(vec![StateMessage::ExportCommandLine]))]
+    #[case("header", Ok(vec![StateMessage::ShowHeader]))]
+    #[case("fitread", Ok(vec![StateMessage::FitRead]))]
+    #[case("ins", Ok(vec![StateMessage::ShowInsertions]))]
+    #[case("filter clear", Ok(vec![StateMessage::ClearQuickFilter]))]
+    #[case("filter", Err("Usage: filter clear".to_string()))]
+    #[case("find gene_name=TP53", Ok(vec![StateMessage::FindFeatures(FeatureQuery { attribute: FeatureAttribute::GeneName, value: "TP53".to_string() })]))]
+    #[case("find TP53", Err("Invalid find: TP53. Usage: find gene_name|transcript_id|strand=value".to_string()))]
     #[case("chr1:1000", Ok(vec![StateMessage::GotoContigCoordinate("chr1".to_string(), 1000)]))]
     #[case("17:7572659", Ok(vec![StateMessage::GotoContigCoordinate("17".to_string(), 7572659)]))]
     #[case("TP53", Ok(vec![StateMessage::GoToGene("TP53".to_string())]))]
     #[case("invalid:command:format", Err("Invalid command mode input: invalid:command:format".to_string()))]
     #[case("chr1:invalid", Err("Invalid command mode input: chr1:invalid".to_string()))]
+    #[case("goto 17:7572659", Ok(vec![StateMessage::GotoContigCoordinate("17".to_string(), 7572659)]))]
+    #[case("chr7:140753336 A>T", Ok(vec![StateMessage::GoToVariant(VariantLocus {
+        contig: "chr7".to_string(),
+        position: 140753336,
+        reference_allele: "A".to_string(),
+        alternative_allele: "T".to_string(),
+    })]))]
+    #[case("goto 7:140753336ac>a", Ok(vec![StateMessage::GoToVariant(VariantLocus {
+        contig: "7".to_string(),
+        position: 140753336,
+        reference_allele: "AC".to_string(),
+        alternative_allele: "A".to_string(),
+    })]))]
+    #[case("export-pileup out.txt", Ok(vec![StateMessage::ExportPileup("out.txt".to_string())]))]
+    #[case("export-pileup", Err("Usage: export-pileup <path>".to_string()))]
+    #[case("view save a", Ok(vec![StateMessage::SaveView(ViewSlot::A)]))]
+    #[case("view B", Ok(vec![StateMessage::RestoreView(ViewSlot::B)]))]
+    #[case("view save c", Err("Invalid view: save c. Usage: view save a|b | view a|b".to_string()))]
+    #[case("guide 12345", Ok(vec![StateMessage::AddGuide(None, 12345, None)]))]
+    #[case("guide chr1:12345 splice site", Ok(vec![StateMessage::AddGuide(Some("chr1".to_string()), 12345, Some("splice site".to_string()))]))]
+    #[case("guide clear", Ok(vec![StateMessage::ClearGuides]))]
+    #[case("guide", Err("Invalid guide: . Usage: guide [contig:]position [label] | guide clear".to_string()))]
+    #[case("gaps", Ok(vec![StateMessage::ListGaps]))]
+    #[case("track dim genes 50", Ok(vec![StateMessage::SetTrackDim(TrackKind::Genes, 50)]))]
+    #[case("track dim coverage 0%", Ok(vec![StateMessage::SetTrackDim(TrackKind::Coverage, 0)]))]
+    #[case("track dim genes 150", Err("Invalid track: dim genes 150. Usage: track dim cytobands|coverage|reads|sequence|genes 0-100".to_string()))]
+    #[case("track dim variants 50", Err("Invalid track: dim variants 50. Usage: track dim cytobands|coverage|reads|sequence|genes 0-100".to_string()))]
+    #[case("step 1000", Ok(vec![StateMessage::SetTileStep(Some(1000))]))]
+    #[case("step", Ok(vec![StateMessage::SetTileStep(None)]))]
+    #[case("step 0", Err("Invalid step: 0. Usage: step [bases]".to_string()))]
+    #[case("cov agg max", Ok(vec![StateMessage::SetCoverageAggregation(CoverageAggregation::Max)]))]
+    #[case("set coverage-baseline middle", Ok(vec![StateMessage::SetCoverageBaseline(CoverageBaseline::Middle)]))]
+    #[case("set sequence_anchor top", Ok(vec![StateMessage::SetSequenceAnchor(SequenceAnchor::Top)]))]
+    #[case("set sequence_anchor left", Err("Invalid value for sequence_anchor: left".to_string()))]
+    #[case("set insertions hidden", Ok(vec![StateMessage::SetInsertionDisplay(InsertionDisplay::Hidden)]))]
+    #[case("set insertions inline", Err("Invalid value for insertions: inline".to_string()))]
+    #[case("set min_mapq 30", Ok(vec![StateMessage::SetMinMapq(30)]))]
+    #[case("set min_mapq 300", Err("Invalid value for min_mapq: 300".to_string()))]
+    #[case("set mapq-255-meaning max", Ok(vec![StateMessage::SetMapq255Meaning(Mapq255Meaning::Max)]))]
+    #[case("set read_names on", Ok(vec![StateMessage::SetReadNames(true)]))]
+    #[case("set read_names yes", Err("Invalid value for read_names: yes".to_string()))]
+    #[case("set min_baseq 20", Err("Unknown setting: min_baseq".to_string()))]
+    #[case("cov agg median", Err("Invalid cov: agg median. Usage: cov agg mean|max|min | cov clips on|off".to_string()))]
+    #[case("cov clips on", Ok(vec![StateMessage::SetCoverageClips(true)]))]
+    #[case("cov clips off", Ok(vec![StateMessage::SetCoverageClips(false)]))]
+    #[case("cov clips yes", Err("Invalid cov: clips yes. Usage: cov agg mean|max|min | cov clips on|off".to_string()))]
+    #[case("chr7:140753336 A>X", Err("Invalid command mode input: chr7:140753336 A>X".to_string()))]
     fn test_command_parse(
         #[case] input: &str,
         #[case] expected: Result<Vec<StateMessage>, String>,
@@ -330,6 +729,19 @@ mod tests {
         assert_eq!(register.parse(), expected);
     }
 
+    #[rstest]
+    #[case("1234", Ok(vec![StateMessage::GotoCoordinate(1234)]))]
+    #[case(" chr1:1000\n", Ok(vec![StateMessage::GotoContigCoordinate("chr1".to_string(), 1000)]))]
+    #[case("TP53", Ok(vec![StateMessage::GoToGene("TP53".to_string())]))]
+    #[case("goto 17:7572659", Ok(vec![StateMessage::GotoContigCoordinate("17".to_string(), 7572659)]))]
+    #[case("q", Err("Cannot navigate to pasted text: q".to_string()))]
+    #[case("h", Err("Cannot navigate to pasted text: h".to_string()))]
+    #[case("export-pileup out.txt", Err("Cannot navigate to pasted text: export-pileup out.txt".to_string()))]
+    #[case("chr1:invalid", Err("Cannot navigate to pasted text: chr1:invalid".to_string()))]
+    fn test_parse_paste(#[case] input: &str, #[case] expected: Result<Vec<StateMessage>, String>) {
+        assert_eq!(CommandModeRegister::parse_paste(input), expected);
+    }
+
     #[rstest]
     #[case("",KeyCode::Char('g'), Ok(vec![StateMessage::AddCharToNormalModeRegisters('g')]))]
     #[case("g",KeyCode::Char('g'), Err("Invalid input: g".to_string()))]
@@ -355,6 +767,20 @@ mod tests {
     #[case("g", KeyCode::Char('x'), Err("Invalid normal mode input: gx".to_string()))]
     #[case("3", KeyCode::Char('x'), Err("Invalid normal mode input: 3x".to_string()))]
     #[case("3g", KeyCode::Char('x'), Err("Invalid normal mode input: 3gx".to_string()))]
+    #[case("", KeyCode::Char(']'), Ok(vec![StateMessage::AddCharToNormalModeRegisters(']')]))]
+    #[case("]", KeyCode::Char('N'), Ok(vec![StateMessage::GotoNextGap(1), StateMessage::ClearNormalModeRegisters]))]
+    #[case("3[", KeyCode::Char('N'), Ok(vec![StateMessage::GotoPreviousGap(3), StateMessage::ClearNormalModeRegisters]))]
+    #[case("[", KeyCode::Char('e'), Ok(vec![StateMessage::ScrollErrorsBack(1), StateMessage::ClearNormalModeRegisters]))]
+    #[case("2]", KeyCode::Char('e'), Ok(vec![StateMessage::ScrollErrorsForward(2), StateMessage::ClearNormalModeRegisters]))]
+    #[case("]", KeyCode::Char('s'), Ok(vec![StateMessage::StepForward(1), StateMessage::ClearNormalModeRegisters]))]
+    #[case("4[", KeyCode::Char('s'), Ok(vec![StateMessage::StepBackward(4), StateMessage::ClearNormalModeRegisters]))]
+    #[case("]", KeyCode::Char('x'), Err("Invalid normal mode input: ]x".to_string()))]
+    #[case("", KeyCode::Char('Y'), Ok(vec![StateMessage::YankSequence, StateMessage::ClearNormalModeRegisters]))]
+    #[case("", KeyCode::Char('C'), Ok(vec![StateMessage::CycleReadColoring, StateMessage::ClearNormalModeRegisters]))]
+    #[case("", KeyCode::Char('f'), Ok(vec![StateMessage::QuickFilter(true), StateMessage::ClearNormalModeRegisters]))]
+    #[case("", KeyCode::Char('F'), Ok(vec![StateMessage::QuickFilter(false), StateMessage::ClearNormalModeRegisters]))]
+    #[case("", KeyCode::Char('n'), Ok(vec![StateMessage::GotoNextMatch(1), StateMessage::ClearNormalModeRegisters]))]
+    #[case("2", KeyCode::Char('N'), Ok(vec![StateMessage::GotoPreviousMatch(2), StateMessage::ClearNormalModeRegisters]))]
     fn test_normal_mode_translate(
         #[case] existing_buffer: &str,
         #[case] key: KeyCode,
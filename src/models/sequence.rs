@@ -1,5 +1,17 @@
 use crate::models::contig::Contig;
 use crate::models::region::Region;
+use clap::ValueEnum;
+
+/// Where the sequence row is placed relative to the alignment.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum SequenceAnchor {
+    /// Above the alignment, next to the top of the pileup.
+    Top,
+
+    /// Below the alignment, next to the track.
+    Bottom,
+}
+
 /// Sequences of a genome region.
 pub struct Sequence {
     /// 1-based genome coordinate of sequence[0].
@@ -58,6 +70,14 @@ impl Sequence {
         )
     }
 
+    /// Base at a position. 1-based.
+    pub fn base_at(&self, position: usize) -> Option<u8> {
+        self.sequence
+            .as_bytes()
+            .get(position.checked_sub(self.start)?)
+            .copied()
+    }
+
     /// Whether the sequence has complete data in [left, right].
     /// 1-based, inclusive.
     pub fn has_complete_data(&self, region: &Region) -> bool {
@@ -65,3 +85,94 @@ impl Sequence {
             && ((region.start >= self.start()) && (region.end <= self.end()))
     }
 }
+
+/// Nucleotides represented by an IUPAC nucleotide code.
+/// Returns an empty slice for characters that are not IUPAC codes.
+pub fn iupac_bases(code: u8) -> &'static [u8] {
+    match code.to_ascii_uppercase() {
+        b'A' => b"A",
+        b'C' => b"C",
+        b'G' => b"G",
+        b'T' | b'U' => b"T",
+        b'R' => b"AG",
+        b'Y' => b"CT",
+        b'S' => b"CG",
+        b'W' => b"AT",
+        b'K' => b"GT",
+        b'M' => b"AC",
+        b'B' => b"CGT",
+        b'D' => b"AGT",
+        b'H' => b"ACT",
+        b'V' => b"ACG",
+        b'N' => b"ACGT",
+        _ => b"",
+    }
+}
+
+/// Whether a read base is consistent with a reference base that may be an IUPAC ambiguity code.
+/// E.g. a read A over a reference R (A/G) is a match. An N read base is uninformative and never
+/// called a mismatch.
+pub fn base_matches_reference(base: u8, reference_base: u8) -> bool {
+    match base.to_ascii_uppercase() {
+        b'N' => true,
+        b'U' => iupac_bases(reference_base).contains(&b'T'),
+        base => iupac_bases(reference_base).contains(&base),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b'A', "A")]
+    #[case(b'C', "C")]
+    #[case(b'G', "G")]
+    #[case(b'T', "T")]
+    #[case(b'U', "T")]
+    #[case(b'R', "AG")]
+    #[case(b'Y', "CT")]
+    #[case(b'S', "CG")]
+    #[case(b'W', "AT")]
+    #[case(b'K', "GT")]
+    #[case(b'M', "AC")]
+    #[case(b'B', "CGT")]
+    #[case(b'D', "AGT")]
+    #[case(b'H', "ACT")]
+    #[case(b'V', "ACG")]
+    #[case(b'N', "ACGT")]
+    #[case(b'-', "")]
+    fn test_base_matches_reference(#[case] reference_base: u8, #[case] expected_matches: &str) {
+        for base in [b'A', b'C', b'G', b'T'] {
+            let expected = expected_matches.as_bytes().contains(&base);
+            assert_eq!(
+                base_matches_reference(base, reference_base),
+                expected,
+                "read {} over reference {}",
+                base as char,
+                reference_base as char
+            );
+            assert_eq!(
+                base_matches_reference(
+                    base.to_ascii_lowercase(),
+                    reference_base.to_ascii_lowercase()
+                ),
+                expected
+            );
+        }
+
+        // N read bases are never mismatches.
+        assert!(base_matches_reference(b'N', reference_base));
+    }
+
+    #[rstest]
+    #[case(10, Some(b'A'))]
+    #[case(13, Some(b'T'))]
+    #[case(9, None)]
+    #[case(14, None)]
+    fn test_base_at(#[case] position: usize, #[case] expected: Option<u8>) {
+        let sequence = Sequence::new(10, "ACGT".to_string(), Contig::chrom("chr1")).unwrap();
+        assert_eq!(sequence.base_at(position), expected);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::TGVError;
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    Client, StatusCode,
+};
+use std::path::{Path, PathBuf};
+
+/// HTTP caching headers of a cached index, sent back to check whether it is still fresh.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    /// One "name: value" line per header.
+    fn parse(content: &str) -> Self {
+        let mut validators = Self::default();
+        for line in content.lines() {
+            match line.split_once(": ") {
+                Some(("etag", value)) => validators.etag = Some(value.to_string()),
+                Some(("last-modified", value)) => {
+                    validators.last_modified = Some(value.to_string())
+                }
+                _ => {}
+            }
+        }
+        validators
+    }
+
+    fn format(&self) -> String {
+        let mut content = String::new();
+        if let Some(etag) = &self.etag {
+            content.push_str(&format!("etag: {}\n", etag));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            content.push_str(&format!("last-modified: {}\n", last_modified));
+        }
+        content
+    }
+}
+
+/// Caches BAM indexes of remote (http / https) BAM files between sessions, so that they are not
+/// downloaded on each launch. A cached index is revalidated with the server's ETag or
+/// Last-Modified header, and downloaded again only if it changed.
+pub struct IndexCacheService {
+    client: Client,
+    directory: PathBuf,
+}
+
+impl IndexCacheService {
+    /// $XDG_CACHE_HOME/tgv/indexes, or ~/.cache/tgv/indexes.
+    pub fn new() -> Result<Self, TGVError> {
+        let cache_home = match std::env::var("XDG_CACHE_HOME") {
+            Ok(cache_home) if !cache_home.is_empty() => PathBuf::from(cache_home),
+            _ => match std::env::var("HOME") {
+                Ok(home) => Path::new(&home).join(".cache"),
+                Err(_) => {
+                    return Err(TGVError::IOError(
+                        "Cannot find a cache directory for remote BAM indexes".to_string(),
+                    ))
+                }
+            },
+        };
+
+        Ok(Self {
+            client: Client::new(),
+            directory: cache_home.join("tgv").join("indexes"),
+        })
+    }
+
+    /// Whether indexes of the BAM path can be cached. s3 and gs paths are left to htslib, which
+    /// handles their authentication.
+    pub fn supports(bam_path: &str) -> bool {
+        bam_path.starts_with("http://") || bam_path.starts_with("https://")
+    }
+
+    /// Local path of the up-to-date index of a remote BAM file, downloading it if needed.
+    /// refresh: download the index even if a cached copy is fresh.
+    /// If the server cannot be reached or fails (5xx), a cached copy is used as is. A client
+    /// error (4xx), e.g. for an index removed from the server, is reported even if it is cached.
+    pub async fn get_index_path(&self, bam_url: &str, refresh: bool) -> Result<String, TGVError> {
+        let index_url = format!("{}.bai", bam_url);
+        let key = cache_key(&index_url);
+        let index_path = self.directory.join(format!("{}.bai", key));
+        let validators_path = self.directory.join(format!("{}.validators", key));
+
+        let cached = !refresh && index_path.exists();
+        let validators = match cached {
+            true => {
+                Validators::parse(&std::fs::read_to_string(&validators_path).unwrap_or_default())
+            }
+            false => Validators::default(),
+        };
+
+        let mut request = self.client.get(&index_url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(_) if cached => return Ok(index_path.to_string_lossy().to_string()),
+            Err(e) => {
+                return Err(TGVError::IOError(format!(
+                    "Cannot download BAM index {}: {}",
+                    index_url, e
+                )))
+            }
+        };
+
+        if cached && is_cached_index_usable(response.status()) {
+            return Ok(index_path.to_string_lossy().to_string());
+        }
+        if !response.status().is_success() {
+            return Err(TGVError::IOError(format!(
+                "Cannot download BAM index {}: HTTP {}{}",
+                index_url,
+                response.status(),
+                if cached {
+                    ". The cached index may be stale and is not used."
+                } else {
+                    ""
+                }
+            )));
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        let validators = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
+        let bytes = response.bytes().await.map_err(|e| {
+            TGVError::IOError(format!("Cannot download BAM index {}: {}", index_url, e))
+        })?;
+
+        // Write to a temporary file first, so that an interrupted download is not mistaken for a
+        // cached index.
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&self.directory)?;
+            let temporary_path = index_path.with_extension("bai.part");
+            std::fs::write(&temporary_path, &bytes)?;
+            std::fs::rename(&temporary_path, &index_path)?;
+            std::fs::write(&validators_path, validators.format())
+        };
+        write().map_err(|e| {
+            TGVError::IOError(format!(
+                "Cannot cache BAM index in {}: {}",
+                self.directory.display(),
+                e
+            ))
+        })?;
+
+        Ok(index_path.to_string_lossy().to_string())
+    }
+}
+
+/// Whether a cached index can be used after the server responded with a non-success status:
+/// not modified, or a server error that does not say the index changed.
+fn is_cached_index_usable(status: StatusCode) -> bool {
+    status == StatusCode::NOT_MODIFIED || status.is_server_error()
+}
+
+/// File name of a cached index: the 64-bit FNV-1a hash of the URL, in hex. Stable across builds,
+/// unlike the standard library hasher. The ETag is not part of the key: it is kept in the
+/// validators file next to the index, and a changed index replaces the entry.
+fn cache_key(url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "cbf29ce484222325")]
+    #[case("a", "af63dc4c8601ec8c")]
+    fn test_cache_key(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(cache_key(url), expected);
+    }
+
+    #[rstest]
+    #[case(StatusCode::NOT_MODIFIED, true)]
+    #[case(StatusCode::SERVICE_UNAVAILABLE, true)]
+    #[case(StatusCode::INTERNAL_SERVER_ERROR, true)]
+    #[case(StatusCode::NOT_FOUND, false)]
+    #[case(StatusCode::GONE, false)]
+    #[case(StatusCode::FORBIDDEN, false)]
+    fn test_is_cached_index_usable(#[case] status: StatusCode, #[case] expected: bool) {
+        assert_eq!(is_cached_index_usable(status), expected);
+    }
+
+    #[rstest]
+    #[case(Validators { etag: Some("\"abc\"".to_string()), last_modified: None })]
+    #[case(Validators {
+        etag: Some("W/\"abc\"".to_string()),
+        last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+    })]
+    #[case(Validators::default())]
+    fn test_validators_round_trip(#[case] validators: Validators) {
+        assert_eq!(Validators::parse(&validators.format()), validators);
+    }
+
+    #[test]
+    fn test_supports() {
+        assert!(IndexCacheService::supports(
+            "https://example.com/sample.bam"
+        ));
+        assert!(!IndexCacheService::supports("s3://bucket/sample.bam"));
+        assert!(!IndexCacheService::supports("sample.bam"));
+    }
+}
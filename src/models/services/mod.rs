@@ -1,2 +1,3 @@
+pub mod indexes;
 pub mod sequences;
 pub mod tracks;
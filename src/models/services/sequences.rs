@@ -1,6 +1,7 @@
 use crate::error::TGVError;
 use crate::models::{contig::Contig, reference::Reference, region::Region, sequence::Sequence};
 use reqwest::Client;
+use rust_htslib::faidx;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -58,3 +59,41 @@ impl SequenceService {
         }
     }
 }
+
+/// Sequences from a local indexed FASTA file, e.g. one haplotype of a phased assembly.
+pub struct FastaSequenceService {
+    reader: faidx::Reader,
+}
+
+impl FastaSequenceService {
+    /// The FASTA file must be indexed (with .fai file in the same directory).
+    pub fn new(path: &str) -> Result<Self, TGVError> {
+        let reader = faidx::Reader::from_path(path)
+            .map_err(|e| TGVError::IOError(format!("Cannot read FASTA file {}: {}", path, e)))?;
+
+        Ok(Self { reader })
+    }
+
+    /// region: 1-based, inclusive.
+    pub fn query_sequence(&self, region: &Region) -> Result<Sequence, TGVError> {
+        // FASTA files may name chromosomes with or without the "chr" prefix.
+        for name in [region.contig.full_name(), region.contig.abbreviated_name()] {
+            if let Ok(sequence) = self.reader.fetch_seq_string(
+                &name,
+                region.start - 1, // 0-based, inclusive.
+                region.end - 1,   // 0-based, inclusive.
+            ) {
+                return Ok(Sequence {
+                    start: region.start,
+                    sequence,
+                    contig: region.contig.clone(),
+                });
+            }
+        }
+
+        Err(TGVError::IOError(format!(
+            "Contig {} not found in the FASTA file",
+            region.contig.full_name()
+        )))
+    }
+}
@@ -1,6 +1,9 @@
 use crate::error::TGVError;
 use crate::models::{
     contig::Contig,
+    density::GeneDensity,
+    find::{FeatureMatch, FeatureQuery},
+    gap::GapIndex,
     reference::Reference,
     region::Region,
     strand::Strand,
@@ -11,7 +14,6 @@ use std::sync::Arc;
 
 pub struct TrackService {
     pool: Arc<MySqlPool>,
-    #[allow(dead_code)]
     reference: Reference,
 }
 
@@ -475,6 +477,86 @@ impl TrackService {
     }
 }
 
+/// Assembly gaps
+impl TrackService {
+    /// Gaps (runs of N) in the reference assembly on a contig.
+    pub async fn query_gaps(&self, contig: &Contig) -> Result<GapIndex, TGVError> {
+        // hg19 splits the gap table by chromosome.
+        let table = match self.reference {
+            Reference::Hg19 => format!("{}_gap", contig.full_name()),
+            Reference::Hg38 => "gap".to_string(),
+        };
+
+        let rows = sqlx::query(&format!(
+            "SELECT chromStart, chromEnd FROM {} WHERE chrom = ? ORDER BY chromStart ASC",
+            table
+        ))
+        .bind(contig.full_name())
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut gaps = Vec::new();
+        for row in rows {
+            let chrom_start: u32 = row.try_get("chromStart")?;
+            let chrom_end: u32 = row.try_get("chromEnd")?;
+
+            // USCS coordinates are 0-based, half-open
+            gaps.push(Region {
+                contig: contig.clone(),
+                start: chrom_start as usize + 1,
+                end: chrom_end as usize,
+            });
+        }
+
+        Ok(GapIndex::new(contig.clone(), gaps))
+    }
+
+    /// Gene starts on the whole contig.
+    pub async fn query_gene_density(&self, contig: &Contig) -> Result<GeneDensity, TGVError> {
+        let rows = sqlx::query("SELECT txStart FROM ncbiRefSeqSelect WHERE chrom = ?")
+            .bind(contig.full_name())
+            .fetch_all(&*self.pool)
+            .await?;
+
+        let mut starts = Vec::new();
+        for row in rows {
+            let tx_start: u64 = row.try_get("txStart")?;
+            starts.push(tx_start as usize + 1); // UCSC is 0-based
+        }
+
+        Ok(GeneDensity::new(contig.clone(), starts))
+    }
+
+    /// Genes on all contigs whose attribute equals the query value.
+    pub async fn query_feature_matches(
+        &self,
+        query: &FeatureQuery,
+    ) -> Result<Vec<FeatureMatch>, TGVError> {
+        // The column comes from a fixed list, so it is safe to format into the query.
+        let rows = sqlx::query(&format!(
+            "SELECT chrom, txStart, name2 FROM ncbiRefSeqSelect WHERE {} = ? ORDER BY chrom, txStart",
+            query.attribute.column()
+        ))
+        .bind(&query.value)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            let chrom: String = row.try_get("chrom")?;
+            let tx_start: u64 = row.try_get("txStart")?;
+            let name2: String = row.try_get("name2")?;
+            matches.push(FeatureMatch {
+                contig: Contig::chrom(&chrom),
+                start: tx_start as usize + 1, // UCSC is 0-based
+                name: name2,
+            });
+        }
+
+        Ok(matches)
+    }
+}
+
 // Helper function to parse BLOB of comma-separated coordinates
 fn parse_blob_to_coords(blob: &[u8]) -> Vec<usize> {
     let coords_str = String::from_utf8_lossy(blob);
@@ -64,14 +64,6 @@ impl GenomeInterval for Gene {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[allow(clippy::upper_case_acronyms)]
-enum ExonPosition {
-    PreCDS,
-    CDS,
-    PostCDS,
-}
-
 impl Gene {
     pub fn get_exon(&self, idx: usize) -> Option<Feature> {
         if idx >= self.exon_starts.len() {
@@ -90,102 +82,116 @@ impl Gene {
         self.exon_starts.len()
     }
 
+    /// Exon / intron number of the i-th exon / intron from the left.
+    /// Numbers are 1-based and follow the transcription direction (5' -> 3').
+    fn feature_number(&self, i: usize, n: usize) -> usize {
+        match self.strand {
+            Strand::Forward => i + 1,
+            Strand::Reverse => n - i,
+        }
+    }
+
+    /// Whether the gene has a coding sequence.
+    pub fn is_coding(&self) -> bool {
+        self.cds_start <= self.cds_end
+    }
+
+    /// Split the gene into CDS exons, UTRs (non-CDS exons), and introns.
+    /// Returns (start, end, feature type, feature number). 1-based, inclusive.
+    /// Exons and UTRs are numbered by the exon they belong to; introns are numbered separately.
     pub fn features(&self) -> Vec<(usize, usize, FeatureType, usize)> {
         // TODO: prevent labeling overlap.
-        let mut features: Vec<(usize, usize, FeatureType)> = Vec::new();
-        let mut last_exon_end = self.transcription_start;
+        let n_exons = self.n_exons();
+        let n_introns = n_exons.saturating_sub(1);
 
-        let mut n_cds_exons = 0;
-        let mut n_introns = 0;
+        let mut features: Vec<(usize, usize, FeatureType, usize)> = Vec::new();
+
+        for (i_exon, (exon_start, exon_end)) in self
+            .exon_starts
+            .iter()
+            .zip(self.exon_ends.iter())
+            .enumerate()
+        {
+            let (exon_start, exon_end) = (*exon_start, *exon_end);
 
-        // Add exon exons
-        for (exon_start, exon_end) in self.exon_starts.iter().zip(self.exon_ends.iter()) {
             // Add intron
-            if *exon_start > last_exon_end {
-                features.push((last_exon_end + 1, *exon_start, FeatureType::Intron));
-                n_introns += 1;
+            if i_exon > 0 {
+                let last_exon_end = self.exon_ends[i_exon - 1];
+                if exon_start > last_exon_end + 1 {
+                    features.push((
+                        last_exon_end + 1,
+                        exon_start - 1,
+                        FeatureType::Intron,
+                        self.feature_number(i_exon - 1, n_introns),
+                    ));
+                }
             }
 
-            // Add exon
-            let exon_start_position =
-                match (*exon_start >= self.cds_start, *exon_start <= self.cds_end) {
-                    (true, true) => ExonPosition::CDS,
-                    (false, _) => ExonPosition::PreCDS,
-                    (true, false) => ExonPosition::PostCDS,
-                };
-
-            let exon_end_position = match (*exon_end >= self.cds_start, *exon_end <= self.cds_end) {
-                (true, true) => ExonPosition::CDS,
-                (false, _) => ExonPosition::PreCDS,
-                (true, false) => ExonPosition::PostCDS,
-            };
-
-            match (exon_start_position, exon_end_position) {
-                (ExonPosition::PreCDS, ExonPosition::PreCDS) => {
-                    features.push((*exon_start, *exon_end, FeatureType::NonCDSExon));
-                    n_cds_exons += 1;
-                }
+            // Add exon, split into UTR / CDS / UTR pieces.
+            let exon_number = self.feature_number(i_exon, n_exons);
 
-                (ExonPosition::PreCDS, ExonPosition::CDS) => {
-                    features.push((*exon_start, self.cds_start - 1, FeatureType::NonCDSExon));
-                    features.push((self.cds_start, *exon_end, FeatureType::Exon));
-                    n_cds_exons += 1;
-                }
-                (ExonPosition::PreCDS, ExonPosition::PostCDS) => {
-                    features.push((*exon_start, self.cds_start - 1, FeatureType::NonCDSExon));
-                    features.push((self.cds_start, self.cds_end, FeatureType::Exon));
-                    features.push((self.cds_end + 1, *exon_end, FeatureType::NonCDSExon));
-                    n_cds_exons += 1;
-                }
-                (ExonPosition::CDS, ExonPosition::CDS) => {
-                    features.push((*exon_start, *exon_end, FeatureType::Exon));
-                    n_cds_exons += 1;
-                }
-                (ExonPosition::CDS, ExonPosition::PostCDS) => {
-                    features.push((*exon_start, self.cds_end, FeatureType::Exon));
-                    features.push((self.cds_end + 1, *exon_end, FeatureType::NonCDSExon));
-                    n_cds_exons += 1;
-                }
-                (ExonPosition::PostCDS, ExonPosition::PostCDS) => {
-                    features.push((*exon_start, *exon_end, FeatureType::NonCDSExon));
-                    n_cds_exons += 1;
-                }
-                _ => {} // should not happen
+            if !self.is_coding() || exon_end < self.cds_start || exon_start > self.cds_end {
+                features.push((exon_start, exon_end, FeatureType::NonCDSExon, exon_number));
+                continue;
             }
 
-            last_exon_end = *exon_end;
-        }
-
-        let mut output: Vec<(usize, usize, FeatureType, usize)> = Vec::new();
-        let mut i_cds_exon = 0;
-        let mut i_intron = 0;
-        for (start, end, feature_type) in features {
-            match feature_type {
-                FeatureType::Exon => {
-                    if self.strand == Strand::Forward {
-                        output.push((start, end, feature_type, i_cds_exon + 1));
-                        i_cds_exon += 1;
-                    } else {
-                        output.push((start, end, feature_type, n_cds_exons - i_cds_exon));
-                        i_cds_exon += 1;
-                    }
-                }
-                FeatureType::Intron => {
-                    if self.strand == Strand::Forward {
-                        output.push((start, end, feature_type, i_intron + 1));
-                        i_intron += 1;
-                    } else {
-                        output.push((start, end, feature_type, n_introns - i_intron));
-                        i_intron += 1;
-                    }
-                }
-                FeatureType::NonCDSExon => {
-                    output.push((start, end, feature_type, 0));
-                }
+            if exon_start < self.cds_start {
+                features.push((
+                    exon_start,
+                    self.cds_start - 1,
+                    FeatureType::NonCDSExon,
+                    exon_number,
+                ));
+            }
+
+            features.push((
+                usize::max(exon_start, self.cds_start),
+                usize::min(exon_end, self.cds_end),
+                FeatureType::Exon,
+                exon_number,
+            ));
+
+            if exon_end > self.cds_end {
+                features.push((
+                    self.cds_end + 1,
+                    exon_end,
+                    FeatureType::NonCDSExon,
+                    exon_number,
+                ));
             }
         }
 
-        output
+        features
+    }
+
+    /// Index of the codon covering a position, counted from the start codon in the transcription
+    /// direction. 0-based. None if the position is not in a CDS exon.
+    /// The CDS is assumed to start in frame, from cds_start (forward) or cds_end (reverse).
+    pub fn codon_index_at(&self, position: usize) -> Option<usize> {
+        let cds_exons = self
+            .features()
+            .into_iter()
+            .filter(|(_, _, feature_type, _)| *feature_type == FeatureType::Exon)
+            .map(|(start, end, _, _)| (start, end))
+            .collect::<Vec<(usize, usize)>>();
+
+        if !cds_exons
+            .iter()
+            .any(|(start, end)| *start <= position && position <= *end)
+        {
+            return None;
+        }
+
+        // CDS bases upstream of the position
+        let n_upstream_bases = cds_exons
+            .iter()
+            .map(|(start, end)| match self.strand {
+                Strand::Forward => position.clamp(*start, *end + 1) - start,
+                Strand::Reverse => end - position.clamp(*start - 1, *end),
+            })
+            .sum::<usize>();
+
+        Some(n_upstream_bases / 3)
     }
 }
 
@@ -629,6 +635,83 @@ mod tests {
         }
     }
 
+    /// Gene with exons [1,10], [21,30], [51,100] and CDS [25, 80].
+    fn get_test_gene(strand: Strand) -> Gene {
+        Gene {
+            id: "gene".to_string(),
+            name: "gene".to_string(),
+            strand,
+            contig: Contig::chrom("chr1"),
+            transcription_start: 1,
+            transcription_end: 100,
+            cds_start: 25,
+            cds_end: 80,
+            exon_starts: vec![1, 21, 51],
+            exon_ends: vec![10, 30, 100],
+        }
+    }
+
+    #[rstest]
+    #[case(Strand::Forward, vec![
+        (1, 10, FeatureType::NonCDSExon, 1),
+        (11, 20, FeatureType::Intron, 1),
+        (21, 24, FeatureType::NonCDSExon, 2),
+        (25, 30, FeatureType::Exon, 2),
+        (31, 50, FeatureType::Intron, 2),
+        (51, 80, FeatureType::Exon, 3),
+        (81, 100, FeatureType::NonCDSExon, 3),
+    ])]
+    #[case(Strand::Reverse, vec![
+        (1, 10, FeatureType::NonCDSExon, 3),
+        (11, 20, FeatureType::Intron, 2),
+        (21, 24, FeatureType::NonCDSExon, 2),
+        (25, 30, FeatureType::Exon, 2),
+        (31, 50, FeatureType::Intron, 1),
+        (51, 80, FeatureType::Exon, 1),
+        (81, 100, FeatureType::NonCDSExon, 1),
+    ])]
+    fn test_gene_features(
+        #[case] strand: Strand,
+        #[case] expected: Vec<(usize, usize, FeatureType, usize)>,
+    ) {
+        assert_eq!(get_test_gene(strand).features(), expected);
+    }
+
+    #[rstest]
+    #[case(Strand::Forward, 20, None)]
+    #[case(Strand::Forward, 25, Some(0))]
+    #[case(Strand::Forward, 27, Some(0))]
+    #[case(Strand::Forward, 28, Some(1))]
+    #[case(Strand::Forward, 40, None)]
+    #[case(Strand::Forward, 51, Some(2))]
+    #[case(Strand::Forward, 80, Some(11))]
+    #[case(Strand::Forward, 81, None)]
+    #[case(Strand::Reverse, 80, Some(0))]
+    #[case(Strand::Reverse, 78, Some(0))]
+    #[case(Strand::Reverse, 77, Some(1))]
+    #[case(Strand::Reverse, 51, Some(9))]
+    #[case(Strand::Reverse, 30, Some(10))]
+    #[case(Strand::Reverse, 25, Some(11))]
+    fn test_codon_index_at(
+        #[case] strand: Strand,
+        #[case] position: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(get_test_gene(strand).codon_index_at(position), expected);
+    }
+
+    #[test]
+    fn test_non_coding_gene_features() {
+        let mut gene = get_test_gene(Strand::Forward);
+        // UCSC non-coding genes have cdsStart == cdsEnd.
+        gene.cds_start = 51;
+        gene.cds_end = 50;
+        assert!(gene
+            .features()
+            .iter()
+            .all(|(_, _, feature_type, _)| *feature_type != FeatureType::Exon));
+    }
+
     #[rstest]
     #[case(1, None)]
     #[case(5, Some(2))]
@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// A variant locus in the `contig:position ref>alt` format, e.g. `chr7:140753336 A>T`.
+/// This is the format variants are usually handed off in, and it is typically pasted.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VariantLocus {
+    pub contig: String, // The state object decide if "chr" prefix is needed.
+
+    /// 1-based.
+    pub position: usize,
+
+    pub reference_allele: String,
+    pub alternative_allele: String,
+}
+
+impl VariantLocus {
+    /// Parse `contig:position ref>alt`. The space between the position and alleles is optional.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (left, alternative_allele) = s.trim().split_once('>')?;
+
+        // Split "chr7:140753336 A" into the locus and the reference allele.
+        let left = left.trim_end();
+        let split_at = left.rfind(|c: char| c.is_ascii_digit())? + 1;
+        let (locus, reference_allele) = (&left[..split_at], left[split_at..].trim());
+        let alternative_allele = alternative_allele.trim();
+
+        let (contig, position) = locus.rsplit_once(':')?;
+        let position = position.parse::<usize>().ok()?;
+
+        if contig.is_empty()
+            || !Self::is_valid_allele(reference_allele)
+            || !Self::is_valid_allele(alternative_allele)
+        {
+            return None;
+        }
+
+        Some(Self {
+            contig: contig.to_string(),
+            position,
+            reference_allele: reference_allele.to_uppercase(),
+            alternative_allele: alternative_allele.to_uppercase(),
+        })
+    }
+
+    fn is_valid_allele(allele: &str) -> bool {
+        !allele.is_empty()
+            && allele
+                .chars()
+                .all(|c| matches!(c.to_ascii_uppercase(), 'A' | 'C' | 'G' | 'T' | 'N'))
+    }
+}
+
+impl fmt::Display for VariantLocus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {}>{}",
+            self.contig, self.position, self.reference_allele, self.alternative_allele
+        )
+    }
+}
@@ -0,0 +1,109 @@
+use crate::models::{
+    alignment::{BaseFilter, InsertionDisplay, Mapq255Meaning, ReadColoring},
+    contig::Contig,
+    coverage::{CoverageAggregation, CoverageBaseline},
+    sequence::SequenceAnchor,
+    window::ViewingWindow,
+};
+use crate::settings::Settings;
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::fmt;
+use strum::Display;
+
+/// Tracks that can be dimmed with :track dim.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum TrackKind {
+    Cytobands,
+    Coverage,
+    Reads,
+    Sequence,
+    Genes,
+}
+
+/// In-memory slots for quickly switching between two views.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ViewSlot {
+    A,
+    B,
+}
+
+impl ViewSlot {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "a" => Some(ViewSlot::A),
+            "b" => Some(ViewSlot::B),
+            _ => None,
+        }
+    }
+
+    pub fn other(&self) -> Self {
+        match self {
+            ViewSlot::A => ViewSlot::B,
+            ViewSlot::B => ViewSlot::A,
+        }
+    }
+}
+
+impl fmt::Display for ViewSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViewSlot::A => write!(f, "A"),
+            ViewSlot::B => write!(f, "B"),
+        }
+    }
+}
+
+/// Settings that can be changed during a session (:set, :cov, C), saved with a view.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DisplaySettings {
+    pub coverage_baseline: CoverageBaseline,
+    pub coverage_bin_agg: CoverageAggregation,
+    pub coverage_include_clips: bool,
+    pub sequence_anchor: SequenceAnchor,
+    pub insertions: InsertionDisplay,
+    pub min_mapq: u8,
+    pub mapq_255_meaning: Mapq255Meaning,
+    pub read_names: bool,
+    pub read_coloring: ReadColoring,
+}
+
+impl DisplaySettings {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            coverage_baseline: settings.coverage_baseline,
+            coverage_bin_agg: settings.coverage_bin_agg,
+            coverage_include_clips: settings.coverage_include_clips,
+            sequence_anchor: settings.sequence_anchor,
+            insertions: settings.insertions,
+            min_mapq: settings.min_mapq,
+            mapq_255_meaning: settings.mapq_255_meaning,
+            read_names: settings.read_names,
+            read_coloring: settings.read_coloring,
+        }
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.coverage_baseline = self.coverage_baseline;
+        settings.coverage_bin_agg = self.coverage_bin_agg;
+        settings.coverage_include_clips = self.coverage_include_clips;
+        settings.sequence_anchor = self.sequence_anchor;
+        settings.insertions = self.insertions;
+        settings.min_mapq = self.min_mapq;
+        settings.mapq_255_meaning = self.mapq_255_meaning;
+        settings.read_names = self.read_names;
+        settings.read_coloring = self.read_coloring;
+    }
+}
+
+/// View state saved in a slot: position, zoom, filters, and display modes.
+#[derive(Clone)]
+pub struct SavedView {
+    pub window: ViewingWindow,
+    pub display: DisplaySettings,
+    pub track_dims: HashMap<TrackKind, u8>,
+
+    /// Quick filter (f / F) and the contig it applies to.
+    pub quick_filter: Option<(Contig, BaseFilter)>,
+}
@@ -316,4 +316,12 @@ impl ViewingWindow {
         self.self_correct(area, contig_length);
         Ok(())
     }
+
+    /// Zoom to the smallest zoom that shows [left, right], and center on it.
+    /// 1-based, inclusive.
+    pub fn fit(&mut self, left: usize, right: usize, area: &Rect, contig_length: Option<usize>) {
+        let length = right.saturating_sub(left) + 1;
+        self.zoom = usize::max(length.div_ceil(area.width as usize), 1);
+        self.set_middle(area, left + length / 2, contig_length);
+    }
 }
@@ -1,25 +1,244 @@
 use crate::models::{
-    alignment::{AlignedRead, Alignment},
+    alignment::{AlignedRead, Alignment, InsertionDisplay, ReadColoring, ReadFilter},
+    sequence::Sequence,
     window::{OnScreenCoordinate, ViewingWindow},
 };
-use crate::rendering::colors;
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use crate::rendering::{colors, mismatch::render_mismatches};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
 use rust_htslib::bam::record::Cigar;
 
 /// Render an alignment on the alignment area.
+/// At base resolution, bases that mismatch the reference sequence (if provided) are drawn.
+/// If monochrome, mismatched bases are told apart by text attributes instead of colors.
+/// Insertions are drawn at base resolution as set by insertions.
+/// Reads not admitted by the filter are not drawn.
+/// If read_names, names are drawn in the empty space before reads at base resolution, unless
+/// more than MAX_NAMED_READS reads are visible.
+/// Read bodies are colored as set by coloring.
 pub fn render_alignment(
     area: &Rect,
     buf: &mut Buffer,
     window: &ViewingWindow,
     alignment: &Alignment,
+    sequence: Option<&Sequence>,
+    monochrome: bool,
+    insertions: InsertionDisplay,
+    filter: &ReadFilter,
+    read_names: bool,
+    coloring: ReadColoring,
 ) {
+    // Leftmost onscreen cell of each visible read.
+    let mut read_starts = Vec::new();
+
     // This iterates through all cached reads and re-calculates coordinates for each movement.
     // Consider improvement.
-    for read in alignment.reads.iter() {
-        for (x, y, onscreen_string, style) in get_read_rendering_info(read, window, area) {
-            buf.set_string(x as u16 + area.x, y as u16 + area.y, onscreen_string, style);
+    for read in alignment
+        .reads
+        .iter()
+        .filter(|read| filter.admits(&read.read))
+    {
+        let rendering_info = get_read_rendering_info(read, window, area);
+        if let Some((x, y, _, _)) = rendering_info.iter().min_by_key(|(x, _, _, _)| *x) {
+            read_starts.push((read, *x, *y));
+        }
+
+        for (x, y, onscreen_string, style) in rendering_info {
+            buf.set_string(
+                x as u16 + area.x,
+                y as u16 + area.y,
+                onscreen_string,
+                get_read_coloring_style(style, read.read.is_reverse(), coloring, monochrome),
+            );
+        }
+
+        if let (true, Some(sequence)) = (window.is_basewise(), sequence) {
+            render_mismatches(area, buf, window, read, sequence, monochrome);
+        }
+
+        if window.is_basewise() && insertions != InsertionDisplay::Hidden {
+            render_insertions(area, buf, window, read);
+        }
+
+        if !window.is_basewise() {
+            render_cigar_summary(area, buf, window, read);
         }
     }
+
+    // Names are drawn after all reads, so that they only take space no read is drawn on.
+    if read_names && window.is_basewise() && read_starts.len() <= MAX_NAMED_READS {
+        for (read, x, y) in read_starts {
+            render_read_name(area, buf, read, x, y);
+        }
+    }
+}
+
+/// Style of a read segment under the coloring. Only aligned segments (drawn on MATCH_COLOR) are
+/// tinted, so that clips and mismatches keep their colors. If monochrome, reverse-strand reads are
+/// dimmed instead.
+fn get_read_coloring_style(
+    style: Style,
+    is_reverse: bool,
+    coloring: ReadColoring,
+    monochrome: bool,
+) -> Style {
+    if coloring == ReadColoring::Plain || style.bg != Some(colors::MATCH_COLOR) {
+        return style;
+    }
+
+    match (monochrome, is_reverse) {
+        (true, true) => style.add_modifier(Modifier::DIM),
+        (true, false) => style,
+        (false, true) => style.bg(colors::REVERSE_STRAND_TINT_COLOR),
+        (false, false) => style.bg(colors::FORWARD_STRAND_TINT_COLOR),
+    }
+}
+
+/// Read names are not drawn when more reads are visible, to avoid clutter.
+pub const MAX_NAMED_READS: usize = 50;
+
+/// Draw the read name in the empty cells before the read at onscreen (x, y), right-aligned
+/// against the read. Cells with content are never drawn over.
+fn render_read_name(area: &Rect, buf: &mut Buffer, read: &AlignedRead, x: usize, y: usize) {
+    let gap = (0..x)
+        .rev()
+        .take_while(|&x| buf[(area.x + x as u16, area.y + y as u16)].symbol() == " ")
+        .count();
+
+    // Keep one blank cell after content on the left, if any.
+    let width = if gap == x { gap } else { gap.saturating_sub(1) };
+
+    let label = match get_read_name_label(read.read.qname(), width) {
+        Some(label) => label,
+        None => return,
+    };
+
+    buf.set_string(
+        area.x + (x - label.chars().count()) as u16,
+        area.y + y as u16,
+        label,
+        Style::default().fg(colors::READ_NAME_COLOR),
+    );
+}
+
+/// Read name fitting in width cells, truncated with '…' if needed. None if fewer than
+/// MIN_READ_NAME_WIDTH cells are available.
+fn get_read_name_label(name: &[u8], width: usize) -> Option<String> {
+    const MIN_READ_NAME_WIDTH: usize = 4;
+
+    let name = String::from_utf8_lossy(name);
+    let length = name.chars().count();
+    if width < MIN_READ_NAME_WIDTH.min(length) || length == 0 {
+        return None;
+    }
+
+    if length <= width {
+        Some(name.to_string())
+    } else {
+        Some(name.chars().take(width - 1).chain(['…']).collect())
+    }
+}
+
+/// When zoomed out, mark reads with indels, splices, or clips with a dot at their first visible
+/// cell, so that complex reads can be spotted before zooming in.
+fn render_cigar_summary(area: &Rect, buf: &mut Buffer, window: &ViewingWindow, read: &AlignedRead) {
+    let color = match get_cigar_summary_color(read) {
+        Some(color) => color,
+        None => return,
+    };
+
+    let y = match window.onscreen_y_coordinate(read.y, area) {
+        OnScreenCoordinate::OnScreen(y) => y,
+        _ => return,
+    };
+
+    let x = match (
+        window.onscreen_x_coordinate(read.start, area),
+        window.onscreen_x_coordinate(read.end, area),
+    ) {
+        (OnScreenCoordinate::OnScreen(x), _) => x,
+        (OnScreenCoordinate::Left(_), OnScreenCoordinate::Left(_)) => return,
+        (OnScreenCoordinate::Left(_), _) => 0,
+        _ => return,
+    };
+
+    buf.set_string(
+        area.x + x as u16,
+        area.y + y as u16,
+        "•",
+        Style::default().fg(color).bg(colors::MATCH_COLOR),
+    );
+}
+
+/// Color of the summary glyph, by the most notable CIGAR operation in the read:
+/// indels, then splices, then clips. None for simple reads.
+fn get_cigar_summary_color(read: &AlignedRead) -> Option<Color> {
+    let cigar = read.read.cigar();
+
+    if cigar
+        .iter()
+        .any(|op| matches!(op, Cigar::Ins(_) | Cigar::Del(_)))
+    {
+        Some(colors::CIGAR_INDEL_COLOR)
+    } else if cigar.iter().any(|op| matches!(op, Cigar::RefSkip(_))) {
+        Some(colors::CIGAR_SPLICE_COLOR)
+    } else if cigar
+        .iter()
+        .any(|op| matches!(op, Cigar::SoftClip(_) | Cigar::HardClip(_)))
+    {
+        Some(colors::CIGAR_CLIP_COLOR)
+    } else {
+        None
+    }
+}
+
+fn render_insertions(area: &Rect, buf: &mut Buffer, window: &ViewingWindow, read: &AlignedRead) {
+    let y = match window.onscreen_y_coordinate(read.y, area) {
+        OnScreenCoordinate::OnScreen(y) => y,
+        _ => return,
+    };
+    let style = Style::default()
+        .fg(colors::INSERTION_FOREGROUND_COLOR)
+        .bg(colors::INSERTION_COLOR)
+        .add_modifier(Modifier::BOLD);
+
+    for (position, bases) in read.insertions() {
+        if let OnScreenCoordinate::OnScreen(x) = window.onscreen_x_coordinate(position, area) {
+            buf.set_string(
+                area.x + x as u16,
+                area.y + y as u16,
+                get_insertion_marker(bases.len()),
+                style,
+            );
+        }
+    }
+}
+
+/// Insertion length in one cell. Insertions longer than 9 bases are shown as '+'.
+fn get_insertion_marker(length: usize) -> String {
+    match length {
+        0..=9 => length.to_string(),
+        _ => "+".to_string(),
+    }
+}
+
+/// Tell the user that reads are hidden at the current zoom level.
+pub fn render_zoom_hint(area: &Rect, buf: &mut Buffer) {
+    const HINT: &str = "Zoom in to view reads (z)";
+
+    if area.width < HINT.len() as u16 || area.height < 1 {
+        return;
+    }
+
+    buf.set_string(
+        area.x + (area.width - HINT.len() as u16) / 2,
+        area.y + area.height / 2,
+        HINT,
+        Style::default().fg(colors::MATCH_COLOR),
+    );
 }
 
 fn get_read_rendering_info(
@@ -36,6 +255,14 @@ fn get_read_rendering_info(
         _ => return vec![],
     };
 
+    // Reads spanning the whole window have no visible endpoints. Draw them with a continuous line
+    // so that they are not mistaken for background.
+    let fill = if read.start < viewing_window.left() && read.end > viewing_window.right(area) {
+        SPANNING_READ_FILL
+    } else {
+        READ_FILL
+    };
+
     for (i_cigar_segment, (start_coord, end_coord, style)) in cigar_segments.iter().enumerate() {
         if let Some((x, length)) = OnScreenCoordinate::onscreen_start_and_length(
             &viewing_window.onscreen_x_coordinate(*start_coord, area),
@@ -45,15 +272,19 @@ fn get_read_rendering_info(
             output.push((
                 x,
                 onscreen_y,
-                get_segment_string(length, {
-                    if i_cigar_segment == 0 {
-                        Some(true)
-                    } else if i_cigar_segment == n_cigar_segments - 1 {
-                        Some(false)
-                    } else {
-                        None
-                    }
-                }),
+                get_segment_string(
+                    length,
+                    {
+                        if i_cigar_segment == 0 {
+                            Some(true)
+                        } else if i_cigar_segment == n_cigar_segments - 1 {
+                            Some(false)
+                        } else {
+                            None
+                        }
+                    },
+                    fill,
+                ),
                 *style,
             ));
         }
@@ -61,19 +292,24 @@ fn get_read_rendering_info(
     output
 }
 
-fn get_segment_string(length: usize, is_reverse: Option<bool>) -> String {
+const READ_FILL: &str = "-";
+const SPANNING_READ_FILL: &str = "─";
+
+fn get_segment_string(length: usize, is_reverse: Option<bool>, fill: &str) -> String {
     match is_reverse {
         Some(true) => (0..length)
-            .map(|i| if i == 0 { "<" } else { "-" })
+            .map(|i| if i == 0 { "<" } else { fill })
             .collect::<String>(),
         Some(false) => (0..length)
-            .map(|i| if i == length - 1 { ">" } else { "-" })
+            .map(|i| if i == length - 1 { ">" } else { fill })
             .collect::<String>(),
-        None => "-".repeat(length),
+        None => fill.repeat(length),
     }
 }
 
 /// Render a read as sections of styled texts
+/// Softclips are drawn base by base. Hardclips are not in the read sequence, so only their extent
+/// is drawn, without a background.
 /// See: https://samtools.github.io/hts-specs/SAMv1.pdf
 fn get_cigar_segments(read: &AlignedRead) -> Vec<(usize, usize, Style)> {
     let mut reference_pivot: usize = read.start; // used in the output
@@ -82,13 +318,24 @@ fn get_cigar_segments(read: &AlignedRead) -> Vec<(usize, usize, Style)> {
     let mut output = Vec::new();
 
     for op in read.read.cigar().iter() {
-        if let Cigar::SoftClip(l) = op {
-            for i_base in query_pivot..query_pivot + *l as usize {
-                let base_coord_is_valid = reference_pivot + i_base >= 1 + read.leading_softclips;
-                if base_coord_is_valid {
-                    let abs_start = reference_pivot + i_base - read.leading_softclips;
+        match op {
+            Cigar::SoftClip(l) => {
+                // Leading softclips end right before the aligned start. Trailing softclips start
+                // right after the aligned end.
+                let clip_start = if query_pivot == 0 {
+                    reference_pivot as isize - *l as isize
+                } else {
+                    reference_pivot as isize
+                };
+
+                for i in 0..*l as usize {
+                    let position = clip_start + i as isize;
+                    if position < 1 {
+                        continue;
+                    }
+                    let position = position as usize;
 
-                    let base = read.read.seq()[i_base];
+                    let base = read.read.seq()[query_pivot + i];
                     let base_color = match base {
                         b'A' => colors::SOFTCLIP_A,
                         b'C' => colors::SOFTCLIP_C,
@@ -96,9 +343,28 @@ fn get_cigar_segments(read: &AlignedRead) -> Vec<(usize, usize, Style)> {
                         b'T' => colors::SOFTCLIP_T,
                         _ => colors::SOFTCLIP_N,
                     };
-                    output.push((abs_start, abs_start, Style::default().bg(base_color)));
+                    output.push((position, position, Style::default().bg(base_color)));
+                }
+            }
+            Cigar::HardClip(l) => {
+                // Hardclips are outside softclips.
+                let (clip_start, clip_end) = if query_pivot == 0 {
+                    let clip_end = read.start as isize - read.leading_softclips as isize - 1;
+                    (clip_end - *l as isize + 1, clip_end)
+                } else {
+                    let clip_start = (read.end + read.trailing_softclips + 1) as isize;
+                    (clip_start, clip_start + *l as isize - 1)
+                };
+
+                if clip_end >= 1 {
+                    output.push((
+                        clip_start.max(1) as usize,
+                        clip_end as usize,
+                        Style::default().fg(colors::HARDCLIP_COLOR),
+                    ));
                 }
             }
+            _ => {}
         }
 
         if consumes_reference(op) {
@@ -108,7 +374,6 @@ fn get_cigar_segments(read: &AlignedRead) -> Vec<(usize, usize, Style)> {
                 get_cigar_style(op),
             ));
             reference_pivot += op.len() as usize;
-            // Note that softclip does not consume query and is handled above.
         }
 
         if consumes_query(op) {
@@ -123,7 +388,7 @@ fn get_cigar_segments(read: &AlignedRead) -> Vec<(usize, usize, Style)> {
 /// Yes: M/D/N/=/X
 /// No: I/S/H/P
 /// See: https://samtools.github.io/hts-specs/SAMv1.pdf
-fn consumes_reference(op: &Cigar) -> bool {
+pub(super) fn consumes_reference(op: &Cigar) -> bool {
     match op {
         Cigar::Match(_l)
         | Cigar::Del(_l)
@@ -138,7 +403,7 @@ fn consumes_reference(op: &Cigar) -> bool {
 /// Whether the cigar operation consumes query.
 /// Yes: M/I/S/=/X
 /// No: D/N/H/P
-fn consumes_query(op: &Cigar) -> bool {
+pub(super) fn consumes_query(op: &Cigar) -> bool {
     match op {
         Cigar::Match(_l)
         | Cigar::Ins(_l)
@@ -162,3 +427,100 @@ fn get_cigar_style(op: &Cigar) -> Style {
         _ => Style::default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::alignment::ClipLengths;
+    use rstest::rstest;
+    use rust_htslib::bam::record::{CigarString, Record};
+
+    #[rstest]
+    #[case(ReadColoring::Plain, false, false, Style::default().bg(colors::MATCH_COLOR))]
+    #[case(ReadColoring::StrandTint, false, false, Style::default().bg(colors::FORWARD_STRAND_TINT_COLOR))]
+    #[case(ReadColoring::StrandTint, true, false, Style::default().bg(colors::REVERSE_STRAND_TINT_COLOR))]
+    #[case(ReadColoring::StrandTint, true, true, Style::default().bg(colors::MATCH_COLOR).add_modifier(Modifier::DIM))]
+    #[case(ReadColoring::StrandTint, false, true, Style::default().bg(colors::MATCH_COLOR))]
+    fn test_get_read_coloring_style(
+        #[case] coloring: ReadColoring,
+        #[case] is_reverse: bool,
+        #[case] monochrome: bool,
+        #[case] expected: Style,
+    ) {
+        let style = Style::default().bg(colors::MATCH_COLOR);
+        assert_eq!(
+            get_read_coloring_style(style, is_reverse, coloring, monochrome),
+            expected
+        );
+
+        // Clips keep their colors.
+        let clip_style = Style::default().bg(colors::SOFTCLIP_A);
+        assert_eq!(
+            get_read_coloring_style(clip_style, is_reverse, coloring, monochrome),
+            clip_style
+        );
+    }
+
+    #[rstest]
+    #[case(b"read1", 10, Some("read1"))]
+    #[case(b"read1", 5, Some("read1"))]
+    #[case(b"read1", 4, Some("rea…"))]
+    #[case(b"read1", 3, None)]
+    #[case(b"r1", 2, Some("r1"))]
+    #[case(b"", 10, None)]
+    fn test_get_read_name_label(
+        #[case] name: &[u8],
+        #[case] width: usize,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            get_read_name_label(name, width),
+            expected.map(|label| label.to_string())
+        );
+    }
+
+    #[test]
+    fn test_cigar_segments_with_clips() {
+        // 4H2S3M1S2H at position 10: hardclips at 4-7 and 14-15, softclips at 8-9 and 13.
+        let mut record = Record::new();
+        let cigar = CigarString(vec![
+            Cigar::HardClip(4),
+            Cigar::SoftClip(2),
+            Cigar::Match(3),
+            Cigar::SoftClip(1),
+            Cigar::HardClip(2),
+        ]);
+        record.set(b"read1", Some(&cigar), b"ACGTAC", &[30; 6]);
+        record.set_pos(9);
+
+        let clips = ClipLengths::from_cigar(&record.cigar());
+        let read = AlignedRead {
+            end: record.cigar().end_pos() as usize,
+            read: record,
+            start: 10,
+            leading_softclips: clips.leading_softclips,
+            trailing_softclips: clips.trailing_softclips,
+            leading_hardclips: clips.leading_hardclips,
+            trailing_hardclips: clips.trailing_hardclips,
+            y: 0,
+        };
+
+        assert_eq!(
+            get_cigar_segments(&read),
+            vec![
+                (4, 7, Style::default().fg(colors::HARDCLIP_COLOR)),
+                (8, 8, Style::default().bg(colors::SOFTCLIP_A)),
+                (9, 9, Style::default().bg(colors::SOFTCLIP_C)),
+                (10, 12, Style::default().bg(colors::MATCH_COLOR)),
+                (13, 13, Style::default().bg(colors::SOFTCLIP_C)),
+                (14, 15, Style::default().fg(colors::HARDCLIP_COLOR)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_insertion_marker() {
+        assert_eq!(get_insertion_marker(2), "2");
+        assert_eq!(get_insertion_marker(12), "+");
+    }
+}
@@ -9,11 +9,45 @@ use ratatui::style::Color;
 pub const MATCH_COLOR: Color = tailwind::GRAY.c500;
 #[allow(dead_code)]
 pub const MISMATCH_COLOR: Color = Color::Rgb(251, 198, 207);
+pub const BEYOND_REFERENCE_COLOR: Color = tailwind::RED.c900;
 pub const SOFTCLIP_A: Color = Color::LightRed;
 pub const SOFTCLIP_C: Color = Color::LightGreen;
 pub const SOFTCLIP_G: Color = Color::LightBlue;
 pub const SOFTCLIP_T: Color = Color::LightYellow;
 pub const SOFTCLIP_N: Color = Color::LightMagenta;
+pub const HARDCLIP_COLOR: Color = tailwind::GRAY.c500;
+pub const INSERTION_COLOR: Color = tailwind::PURPLE.c400;
+pub const INSERTION_FOREGROUND_COLOR: Color = tailwind::GRAY.c50;
+pub const READ_NAME_COLOR: Color = tailwind::GRAY.c400;
+
+// SAM header overlay
+pub const HEADER_RECORD_TYPE_COLOR: Color = tailwind::AMBER.c400;
+
+// Reads colored by strand
+pub const FORWARD_STRAND_TINT_COLOR: Color = tailwind::ROSE.c300;
+pub const REVERSE_STRAND_TINT_COLOR: Color = tailwind::SKY.c300;
+
+// Secondary ruler of another build
+pub const LIFTOVER_COLOR: Color = tailwind::TEAL.c400;
+
+// Primer footprints
+pub const PRIMER_COLOR: Color = tailwind::SLATE.c800;
+pub const PRIMER_DIMMED_READ_COLOR: Color = tailwind::GRAY.c700;
+
+// CIGAR summary glyphs at zoomed-out views
+pub const CIGAR_INDEL_COLOR: Color = tailwind::PURPLE.c500;
+pub const CIGAR_SPLICE_COLOR: Color = tailwind::BLUE.c500;
+pub const CIGAR_CLIP_COLOR: Color = tailwind::ORANGE.c500;
+
+// Coverage
+pub const COVERAGE_BASELINE_COLOR: Color = tailwind::GRAY.c500;
+pub const COVERAGE_CLIP_COLOR: Color = tailwind::AMBER.c400;
+
+// Guides
+pub const GUIDE_COLOR: Color = tailwind::AMBER.c400;
+
+// Command palette
+pub const PALETTE_SELECTED_COLOR: Color = tailwind::GRAY.c700;
 
 // Cytoband
 pub const HIGHLIGHT_COLOR: Color = tailwind::RED.c800;
@@ -24,6 +58,14 @@ pub const GPOS50_COLOR: Color = tailwind::GREEN.c500;
 pub const GPOS75_COLOR: Color = tailwind::GREEN.c700;
 pub const GPOS100_COLOR: Color = tailwind::GREEN.c900;
 
+// Gene density heatmap, from sparse to dense
+pub const GENE_DENSITY_COLORS: [Color; 4] = [
+    tailwind::ORANGE.c900,
+    tailwind::ORANGE.c700,
+    tailwind::ORANGE.c500,
+    tailwind::ORANGE.c300,
+];
+
 pub const ACEN_COLOR: Color = tailwind::RED.c300;
 pub const GVAR_COLOR: Color = CYTOBAND_DEFAULT_COLOR;
 pub const STALK_COLOR: Color = CYTOBAND_DEFAULT_COLOR;
@@ -36,3 +78,13 @@ pub const BASE_C: Color = tailwind::GREEN.c300;
 pub const BASE_G: Color = tailwind::BLUE.c300;
 pub const BASE_T: Color = tailwind::YELLOW.c300;
 pub const BASE_N: Color = tailwind::GRAY.c300;
+
+// Alternate codons in a CDS
+pub const CODON_BAND_A: Color = tailwind::RED.c400;
+pub const CODON_BAND_C: Color = tailwind::GREEN.c400;
+pub const CODON_BAND_G: Color = tailwind::BLUE.c400;
+pub const CODON_BAND_T: Color = tailwind::YELLOW.c400;
+pub const CODON_BAND_N: Color = tailwind::GRAY.c400;
+
+// Consensus
+pub const CONSENSUS_AMBIGUOUS_COLOR: Color = tailwind::FUCHSIA.c300;
@@ -0,0 +1,74 @@
+use crate::models::consensus::is_ambiguity_code;
+use crate::rendering::colors;
+use crate::rendering::sequence::{get_base_color, get_base_monochrome_style};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render the consensus of the reads, one base per column.
+/// IUPAC ambiguity codes (heterozygous sites) are highlighted. Columns without enough depth
+/// (None) are drawn as gaps.
+pub fn render_consensus(area: &Rect, buf: &mut Buffer, consensus: &[Option<u8>], monochrome: bool) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    for (i, base) in consensus.iter().take(area.width as usize).enumerate() {
+        let (symbol, style) = match base {
+            None => ("-".to_string(), Style::default().fg(colors::MATCH_COLOR)),
+            Some(base) if is_ambiguity_code(*base) => (
+                (*base as char).to_string(),
+                if monochrome {
+                    Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+                } else {
+                    Style::default()
+                        .fg(colors::SEQUENCE_FOREGROUND_COLOR)
+                        .bg(colors::CONSENSUS_AMBIGUOUS_COLOR)
+                        .add_modifier(Modifier::BOLD)
+                },
+            ),
+            Some(base) => {
+                let base = *base as char;
+                (
+                    base.to_string(),
+                    if monochrome {
+                        get_base_monochrome_style(base)
+                    } else {
+                        Style::default().fg(get_base_color(base))
+                    },
+                )
+            }
+        };
+
+        buf.set_string(area.x + i as u16, area.y, symbol, style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_consensus() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        render_consensus(
+            &area,
+            &mut buf,
+            &[Some(b'A'), Some(b'R'), None, Some(b'T'), Some(b'G')],
+            false,
+        );
+
+        let row = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect::<String>();
+        assert_eq!(row, "AR-T");
+        assert_eq!(buf[(0, 0)].fg, colors::BASE_A);
+        assert_eq!(buf[(1, 0)].bg, colors::CONSENSUS_AMBIGUOUS_COLOR);
+    }
+}
@@ -6,37 +6,72 @@ use ratatui::{
 };
 
 /// Render the command mode console.
-const MIN_AREA_WIDTH: u16 = 2;
+/// Input longer than the area scrolls horizontally to keep the cursor visible. Hidden input is
+/// marked with an ellipsis.
+const MIN_AREA_WIDTH: u16 = 4;
 const MIN_AREA_HEIGHT: u16 = 1;
 pub fn render_console(area: &Rect, buf: &mut Buffer, command_mode_register: &CommandModeRegister) {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return;
     }
 
-    let input = command_mode_register.input();
+    let input = command_mode_register.input().chars().collect::<Vec<char>>();
     let cursor_position = command_mode_register.cursor_position();
 
-    let cursor_char = if cursor_position >= input.len() {
-        ' '
-    } else {
-        input.chars().nth(cursor_position).unwrap()
-    };
-    let cursor_char_position = area.x + 1 + cursor_position as u16;
-    let cursor_char_style = Style::default().bg(Color::Red);
-
-    buf.set_stringn(area.x, area.y, ":", area.width as usize, Style::default());
-    buf.set_stringn(
-        area.x + 1,
-        area.y,
-        input,
-        area.width as usize - 1,
-        Style::default(),
-    );
-    buf.set_stringn(
-        area.x + cursor_char_position,
+    // Columns after the ':'. The cursor can sit one past the end of the input.
+    let input_width = area.width as usize - 1;
+    let offset = (cursor_position + 1).saturating_sub(input_width);
+
+    let visible_input = input
+        .iter()
+        .skip(offset)
+        .take(input_width)
+        .collect::<String>();
+
+    buf.set_string(area.x, area.y, ":", Style::default());
+    buf.set_string(area.x + 1, area.y, visible_input, Style::default());
+
+    if offset > 0 {
+        buf.set_string(area.x + 1, area.y, "…", Style::default());
+    }
+    if input.len() > offset + input_width {
+        buf.set_string(area.x + area.width - 1, area.y, "…", Style::default());
+    }
+
+    let cursor_char = input.get(cursor_position).copied().unwrap_or(' ');
+    buf.set_string(
+        area.x + 1 + (cursor_position - offset) as u16,
         area.y,
         cursor_char.to_string(),
-        area.width as usize - cursor_char_position as usize,
-        cursor_char_style,
+        Style::default().bg(Color::Red),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("goto", 4, ":goto    ")]
+    #[case("goto chr1:1000", 14, ":…1:1000 ")]
+    #[case("goto chr1:1000", 2, ":goto ch…")]
+    fn test_render_console(
+        #[case] input: &str,
+        #[case] cursor_position: usize,
+        #[case] expected: &str,
+    ) {
+        let area = Rect::new(0, 0, 9, 1);
+        let mut buf = Buffer::empty(area);
+        let mut register = CommandModeRegister::new();
+        input.chars().for_each(|c| register.add_char(c));
+        register.move_cursor_left(input.len() - cursor_position);
+
+        render_console(&area, &mut buf, &register);
+
+        let row = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect::<String>();
+        assert_eq!(row, expected);
+    }
+}
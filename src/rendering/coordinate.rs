@@ -1,15 +1,20 @@
+use crate::models::liftover::Liftover;
 use crate::models::window::{OnScreenCoordinate, ViewingWindow};
+use crate::rendering::colors;
 use itertools::izip;
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
 
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
+/// Render the coordinate markers. If a liftover is provided, positions in the other build are
+/// shown in a third row.
 pub fn render_coordinates(
     area: &Rect,
     buf: &mut Buffer,
     viewing_window: &ViewingWindow,
     contig_length: Option<usize>,
+    liftover: Option<&Liftover>,
 ) -> Result<(), ()> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return Ok(());
@@ -41,9 +46,107 @@ pub fn render_coordinates(
         }
     }
 
+    render_span(area, buf, viewing_window, contig_length);
+
+    if let Some(liftover) = liftover {
+        render_lifted_coordinates(area, buf, viewing_window, liftover, &markers_onscreen_x);
+    }
+
     Ok(())
 }
 
+/// Secondary ruler in the third row: a line where positions map to the other build, broken where
+/// they do not, with lifted positions at the primary markers. The contig of the other build is
+/// shown with the first label, and again whenever it changes.
+fn render_lifted_coordinates(
+    area: &Rect,
+    buf: &mut Buffer,
+    viewing_window: &ViewingWindow,
+    liftover: &Liftover,
+    markers_onscreen_x: &[u16],
+) {
+    if area.height < 3 {
+        return;
+    }
+
+    let y = area.y + 2;
+    let style = Style::default().fg(colors::LIFTOVER_COLOR);
+    let contig = viewing_window.contig.full_name();
+    let position_at = |x: u16| viewing_window.left() + x as usize * viewing_window.zoom();
+
+    for x in 0..area.width {
+        if liftover.lift(&contig, position_at(x)).is_some() {
+            buf.set_string(area.x + x, y, "─", style);
+        }
+    }
+
+    let mut labels_end = 0; // Labels do not overlap.
+    let mut last_query_contig: Option<String> = None;
+    for marker_x in markers_onscreen_x {
+        let (query_contig, position) = match liftover.lift(&contig, position_at(*marker_x)) {
+            Some(lifted) => lifted,
+            None => continue,
+        };
+
+        let text = if last_query_contig.as_deref() == Some(query_contig) {
+            to_thousand_separated(position)
+        } else {
+            format!("{}:{}", query_contig, to_thousand_separated(position))
+        };
+        let text_x = marker_x.saturating_sub(text.len() as u16 / 2);
+        if text_x < labels_end || text_x as usize + text.len() > area.width as usize {
+            continue;
+        }
+
+        buf.set_string(area.x + text_x, y, &text, style);
+        labels_end = text_x + text.len() as u16 + 1;
+        last_query_contig = Some(query_contig.to_string());
+    }
+}
+
+/// Show the visible span and zoom level at the right end of the marker row.
+fn render_span(
+    area: &Rect,
+    buf: &mut Buffer,
+    viewing_window: &ViewingWindow,
+    contig_length: Option<usize>,
+) {
+    let right = match contig_length {
+        Some(length) => usize::min(viewing_window.right(area), length),
+        None => viewing_window.right(area),
+    };
+    let span = (right + 1).saturating_sub(viewing_window.left());
+
+    let text = format!(
+        " span: {}  zoom: {}x ",
+        get_span_text(span),
+        viewing_window.zoom()
+    );
+    if text.len() > area.width as usize {
+        return;
+    }
+
+    buf.set_string(
+        area.x + area.width - text.len() as u16,
+        area.y + area.height.min(2) - 1,
+        text,
+        Style::default(),
+    );
+}
+
+/// Human-readable number of bases. e.g. 2.5 kb
+fn get_span_text(span: usize) -> String {
+    if span < 1_000 {
+        format!("{} bp", span)
+    } else if span < 1_000_000 {
+        format!("{:.1} kb", span as f64 / 1_000.0)
+    } else if span < 1_000_000_000 {
+        format!("{:.1} Mb", span as f64 / 1_000_000.0)
+    } else {
+        format!("{:.1} Gb", span as f64 / 1_000_000_000.0)
+    }
+}
+
 const MIN_SPACING_BETWEEN_MARKERS: u16 = 15;
 
 /// Calculate coordinate markers.
@@ -148,3 +251,48 @@ fn to_thousand_separated(number: usize) -> String {
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::contig::Contig;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "0 bp")]
+    #[case(999, "999 bp")]
+    #[case(2_500, "2.5 kb")]
+    #[case(248_956_422, "249.0 Mb")]
+    #[case(3_100_000_000, "3.1 Gb")]
+    fn test_get_span_text(#[case] span: usize, #[case] expected: &str) {
+        assert_eq!(get_span_text(span), expected);
+    }
+
+    #[test]
+    fn test_render_lifted_coordinates() {
+        // chr1:1-20 maps to chr2:1001-1020, and chr1:31-60 to chr2:2001-2030.
+        let liftover = Liftover::from_chain(
+            "chain 100 chr1 100 + 0 60 chr2 5000 + 1000 2030 1\n20 10 980\n30\n",
+        )
+        .unwrap();
+        let area = Rect::new(0, 0, 60, 3);
+        let mut buf = Buffer::empty(area);
+        let window = ViewingWindow::new_basewise_window(Contig::chrom("chr1"), 1, 0);
+        render_lifted_coordinates(&area, &mut buf, &window, &liftover, &[9, 39]);
+
+        let row = (0..area.width)
+            .map(|x| buf[(x, 2)].symbol().to_string())
+            .collect::<String>();
+        assert_eq!(
+            row,
+            format!(
+                "{}chr2:1,010{}{}{}2,010{}",
+                "─".repeat(4),
+                "─".repeat(6),
+                " ".repeat(10),
+                "─".repeat(7),
+                "─".repeat(18)
+            )
+        );
+    }
+}
@@ -1,46 +1,232 @@
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Position, Rect},
     style::Style,
     widgets::{Sparkline, Widget},
 };
 
 use crate::error::TGVError;
 use crate::models::alignment::Alignment;
+use crate::models::coverage::{CoverageAggregation, CoverageBaseline};
+use crate::models::strand::Strand;
 use crate::models::window::ViewingWindow;
+use crate::rendering::colors;
 
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
 /// Render the coverage barplot.
+/// For stranded alignments, forward-strand coverage is drawn above a zero line and reverse-strand
+/// coverage below it, regardless of the baseline setting.
+/// When multiple bases are drawn in one column, their coverage is combined by the aggregation.
+/// If include_clips, soft-clipped bases are counted as well and drawn in a distinct color on top
+/// of the aligned bases. Stranded coverage never includes soft-clipped bases.
 pub fn render_coverage(
     area: &Rect,
     buf: &mut Buffer,
     window: &ViewingWindow,
     alignment: &Alignment,
+    baseline: &CoverageBaseline,
+    aggregation: &CoverageAggregation,
+    include_clips: bool,
 ) -> Result<(), TGVError> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return Ok(());
     }
 
+    if alignment.is_stranded() {
+        let [forward, reverse] = [Strand::Forward, Strand::Reverse].map(|strand| {
+            calculate_binned_coverage(
+                alignment,
+                window.left(),
+                window.right(area),
+                area.width as usize,
+                Some(&strand),
+                aggregation,
+                false,
+            )
+        });
+        let (forward, reverse) = (forward?, reverse?);
+        let y_max =
+            round_up_max_coverage(*forward.iter().chain(reverse.iter()).max().unwrap_or(&0));
+        render_bars_from_baseline(
+            area,
+            buf,
+            &forward,
+            &reverse,
+            y_max,
+            area.height / 2,
+            Style::default(),
+        );
+        return Ok(());
+    }
+
     let binned_coverage = calculate_binned_coverage(
         alignment,
         window.left(),
         window.right(area),
         area.width as usize,
+        None,
+        aggregation,
+        include_clips,
     )?;
 
     let y_max = round_up_max_coverage(*binned_coverage.iter().max().unwrap_or(&0));
 
-    let sparkline = Sparkline::default().data(&binned_coverage).max(y_max);
+    let aligned_coverage = if include_clips {
+        Some(calculate_binned_coverage(
+            alignment,
+            window.left(),
+            window.right(area),
+            area.width as usize,
+            None,
+            aggregation,
+            false,
+        )?)
+    } else {
+        None
+    };
+
+    match baseline {
+        CoverageBaseline::Bottom => {
+            let sparkline = Sparkline::default().data(&binned_coverage).max(y_max);
+
+            sparkline.render(*area, buf);
+
+            if let Some(aligned_coverage) = &aligned_coverage {
+                tint_clipped_rows(area, buf, aligned_coverage, y_max, area.height);
+            }
 
-    sparkline.render(*area, buf);
+            buf.set_string(area.x, area.y, format!("[0-{}]", y_max,), Style::default());
+        }
+        CoverageBaseline::Middle => {
+            render_bars_from_baseline(
+                area,
+                buf,
+                &binned_coverage,
+                &[],
+                y_max,
+                area.height / 2,
+                Style::default(),
+            );
 
-    buf.set_string(area.x, area.y, format!("[0-{}]", y_max,), Style::default());
+            if let Some(aligned_coverage) = &aligned_coverage {
+                tint_clipped_rows(area, buf, aligned_coverage, y_max, area.height / 2);
+            }
+        }
+    }
 
     Ok(())
 }
 
+/// Color the part of upward bars (growing from row baseline_y, relative to area.y) above the
+/// aligned coverage. Rows shared by aligned and soft-clipped bases keep the bar color.
+fn tint_clipped_rows(
+    area: &Rect,
+    buf: &mut Buffer,
+    aligned_coverage: &[u64],
+    y_max: u64,
+    baseline_y: u16,
+) {
+    if y_max == 0 {
+        return;
+    }
+
+    let rows = baseline_y as u64;
+    for (x, value) in aligned_coverage
+        .iter()
+        .take(area.width as usize)
+        .enumerate()
+    {
+        let aligned_rows = (value * rows * 8 / y_max).min(rows * 8).div_ceil(8);
+        for i_row in aligned_rows..rows {
+            let y = area.y + baseline_y - 1 - i_row as u16;
+            if let Some(cell) = buf.cell_mut(Position::new(area.x + x as u16, y)) {
+                cell.set_fg(colors::COVERAGE_CLIP_COLOR);
+            }
+        }
+    }
+}
+
+const UPWARD_BAR_SYMBOLS: [&str; 8] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇"];
+const FULL_BAR_SYMBOL: &str = "█";
+
+/// Render bars from a zero line at row baseline_y (relative to area.y).
+/// upward values grow above the zero line, and downward values (e.g. negative values) grow below it.
+/// Each column can have both.
+/// y_max is the value of a bar filling all rows above the zero line. Rows below use the same scale.
+fn render_bars_from_baseline(
+    area: &Rect,
+    buf: &mut Buffer,
+    upward: &[u64],
+    downward: &[u64],
+    y_max: u64,
+    baseline_y: u16,
+    style: Style,
+) {
+    if baseline_y >= area.height || y_max == 0 {
+        return;
+    }
+
+    let rows_above = baseline_y as u64;
+    let rows_below = (area.height - baseline_y - 1) as u64;
+
+    // Zero line
+    for x in 0..area.width {
+        if let Some(cell) = buf.cell_mut(Position::new(area.x + x, area.y + baseline_y)) {
+            cell.set_symbol("─").set_fg(colors::COVERAGE_BASELINE_COLOR);
+        }
+    }
+
+    // Bar height in 1/8 rows
+    let get_eighths = |value: u64| value * rows_above.max(1) * 8 / y_max;
+
+    for (x, value) in upward.iter().take(area.width as usize).enumerate() {
+        let x = area.x + x as u16;
+        let eighths = get_eighths(*value).min(rows_above * 8);
+
+        for i_row in 0..eighths.div_ceil(8) {
+            let remainder = eighths - i_row * 8;
+            let symbol = if remainder >= 8 {
+                FULL_BAR_SYMBOL
+            } else {
+                UPWARD_BAR_SYMBOLS[remainder as usize]
+            };
+            let y = area.y + baseline_y - 1 - i_row as u16;
+            buf.set_string(x, y, symbol, style);
+        }
+    }
+
+    for (x, value) in downward.iter().take(area.width as usize).enumerate() {
+        let x = area.x + x as u16;
+        let eighths = get_eighths(*value).min(rows_below * 8);
+
+        // Only full and half upper blocks are available for downward bars.
+        for i_row in 0..eighths.div_ceil(8) {
+            let remainder = eighths - i_row * 8;
+            let symbol = match remainder {
+                8.. => FULL_BAR_SYMBOL,
+                4..=7 => "▀",
+                _ => "▔",
+            };
+            let y = area.y + baseline_y + 1 + i_row as u16;
+            buf.set_string(x, y, symbol, style);
+        }
+    }
+
+    // Labels
+    buf.set_string(area.x, area.y, format!("[{}]", y_max), Style::default());
+    buf.set_string(area.x, area.y + baseline_y, "0", Style::default());
+    if rows_below > 0 {
+        buf.set_string(
+            area.x,
+            area.y + area.height - 1,
+            format!("[-{}]", y_max * rows_below / rows_above.max(1)),
+            Style::default(),
+        );
+    }
+}
+
 /// Round up the maximum coverage to two significant digits.
 fn round_up_max_coverage(x: u64) -> u64 {
     if x < 10 {
@@ -111,11 +297,17 @@ fn get_linear_space(
 
 /// Calculate the binned coverage in [left_bound, right_bound].
 /// 1-based, inclusive.
+/// strand: coverage of reads from a transcript strand (stranded alignments only). None for all reads.
+/// aggregation: how bases in a bin are combined when there are more bases than bins.
+/// include_clips: count soft-clipped bases. Ignored for strand coverage.
 fn calculate_binned_coverage(
     alignment: &Alignment,
     left: usize,
     right: usize,
     n_bins: usize,
+    strand: Option<&Strand>,
+    aggregation: &CoverageAggregation,
+    include_clips: bool,
 ) -> Result<Vec<u64>, TGVError> {
     if right < left {
         return Err(TGVError::ValueError("Right is less than left".to_string()));
@@ -125,6 +317,16 @@ fn calculate_binned_coverage(
         return Err(TGVError::ValueError("n_bins is 0".to_string()));
     }
 
+    let coverage_at = |x: usize| -> u64 {
+        match strand {
+            Some(strand) => alignment.strand_coverage_at(x, strand) as u64,
+            None if include_clips => {
+                (alignment.coverage_at(x) + alignment.clipped_coverage_at(x)) as u64
+            }
+            None => alignment.coverage_at(x) as u64,
+        }
+    };
+
     match (right - left + 1).cmp(&n_bins) {
         std::cmp::Ordering::Less => {
             return Err(TGVError::ValueError(
@@ -132,9 +334,7 @@ fn calculate_binned_coverage(
             ))
         }
         std::cmp::Ordering::Equal => {
-            return Ok((left..right + 1)
-                .map(|x| alignment.coverage_at(x) as u64)
-                .collect());
+            return Ok((left..right + 1).map(coverage_at).collect());
         }
         std::cmp::Ordering::Greater => {}
     }
@@ -149,9 +349,26 @@ fn calculate_binned_coverage(
                     "bin_left is greater than bin_right".to_string(),
                 ));
             }
-            Ok(alignment
-                .mean_basewise_coverage_in(*bin_left, *bin_right)
-                .unwrap() as u64)
+            Ok(match aggregation {
+                CoverageAggregation::Mean => {
+                    (match strand {
+                        Some(strand) => {
+                            alignment.mean_strand_coverage_in(*bin_left, *bin_right, strand)?
+                        }
+                        None if include_clips => {
+                            alignment.mean_basewise_coverage_in(*bin_left, *bin_right)?
+                                + alignment.mean_clipped_coverage_in(*bin_left, *bin_right)?
+                        }
+                        None => alignment.mean_basewise_coverage_in(*bin_left, *bin_right)?,
+                    }) as u64
+                }
+                CoverageAggregation::Max => {
+                    (*bin_left..=*bin_right).map(coverage_at).max().unwrap_or(0)
+                }
+                CoverageAggregation::Min => {
+                    (*bin_left..=*bin_right).map(coverage_at).min().unwrap_or(0)
+                }
+            })
         })
         .collect();
 
@@ -178,6 +395,61 @@ mod tests {
         assert_eq!(round_up_max_coverage(input), expected);
     }
 
+    #[rstest]
+    #[case(vec![0, 0, 0, 0, 0, 16, 4, -4], vec![
+        "[16] █  ",
+        "     █▄ ",
+        "0───────",
+        "[-8]   ▀",
+    ])]
+    #[case(vec![0, 0, 0, 0, 0, -16, 2, -1], vec![
+        "[16]    ",
+        "      ▂ ",
+        "0───────",
+        "[-8] █ ▔",
+    ])]
+    fn test_render_bars_from_baseline(#[case] values: Vec<i64>, #[case] expected: Vec<&str>) {
+        let area = Rect::new(0, 0, 8, 4);
+        let mut buf = Buffer::empty(area);
+        let upward = values
+            .iter()
+            .map(|v| v.max(&0).unsigned_abs())
+            .collect::<Vec<u64>>();
+        let downward = values
+            .iter()
+            .map(|v| v.min(&0).unsigned_abs())
+            .collect::<Vec<u64>>();
+        render_bars_from_baseline(&area, &mut buf, &upward, &downward, 16, 2, Style::default());
+
+        for (y, expected_row) in expected.iter().enumerate() {
+            let row = (0..area.width)
+                .map(|x| buf[(x, y as u16)].symbol().to_string())
+                .collect::<String>();
+            assert_eq!(&row, expected_row);
+        }
+    }
+
+    #[test]
+    fn test_tint_clipped_rows() {
+        let area = Rect::new(0, 0, 3, 4);
+        let mut buf = Buffer::empty(area);
+        tint_clipped_rows(&area, &mut buf, &[0, 12, 16], 16, 4);
+
+        let tinted = |x: u16, y: u16| buf[(x, y)].fg == colors::COVERAGE_CLIP_COLOR;
+        assert_eq!(
+            (0..4).map(|y| tinted(0, y)).collect::<Vec<bool>>(),
+            vec![true; 4]
+        );
+        assert_eq!(
+            (0..4).map(|y| tinted(1, y)).collect::<Vec<bool>>(),
+            vec![true, false, false, false]
+        );
+        assert_eq!(
+            (0..4).map(|y| tinted(2, y)).collect::<Vec<bool>>(),
+            vec![false; 4]
+        );
+    }
+
     #[rstest]
     #[case(1, 5, 0, Err(TGVError::ValueError("n_bins is 0".to_string())))]
     #[case(1, 5, 5, Err(TGVError::ValueError("n_bins is greater than the number of bases in the region".to_string())))]
@@ -2,6 +2,7 @@
 ///
 use crate::models::{
     cytoband::{Cytoband, CytobandSegment, Stain},
+    density::GeneDensity,
     window::ViewingWindow,
 };
 use crate::rendering::colors;
@@ -9,7 +10,7 @@ use crate::rendering::colors;
 use ratatui::{
     buffer::Buffer,
     layout::{Position, Rect},
-    style::Style,
+    style::{Color, Style},
 };
 
 const CYTOBAND_TEXT_LEFT_SPACING: u16 = 12;
@@ -86,6 +87,46 @@ pub fn render_cytobands(
     }
 }
 
+/// Gene density heatmap across the whole contig, in the second row under the cytobands.
+/// Denser bins are drawn in brighter colors.
+pub fn render_gene_density(
+    area: &Rect,
+    buf: &mut Buffer,
+    gene_density: &GeneDensity,
+    contig_length: usize,
+) {
+    if area.width <= MIN_AREA_WIDTH || area.height < 2 {
+        return;
+    }
+
+    let y = area.y + 1;
+    let n_bins = (area.width - CYTOBAND_TEXT_RIGHT_SPACING - CYTOBAND_TEXT_LEFT_SPACING) as usize;
+    let counts = gene_density.bin_counts(contig_length, n_bins);
+    let max_count = counts.iter().max().copied().unwrap_or(0);
+
+    for (i_bin, count) in counts.iter().enumerate() {
+        if let Some(color) = get_gene_density_color(*count, max_count) {
+            let x = area.x + CYTOBAND_TEXT_LEFT_SPACING + i_bin as u16;
+            if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                cell.set_char(' ');
+                cell.set_bg(color);
+            }
+        }
+    }
+
+    buf.set_string(area.x, y, "genes", Style::default());
+}
+
+/// None for empty bins.
+fn get_gene_density_color(count: usize, max_count: usize) -> Option<Color> {
+    if count == 0 || max_count == 0 {
+        return None;
+    }
+    let n_levels = colors::GENE_DENSITY_COLORS.len();
+    let level = (count * n_levels).div_ceil(max_count).clamp(1, n_levels);
+    Some(colors::GENE_DENSITY_COLORS[level - 1])
+}
+
 fn get_cytoband_total_length_text(length: usize) -> String {
     let mut length = length;
     let mut power = 0;
@@ -185,3 +226,23 @@ fn get_cytoband_segment_style(cytoband_segment: &CytobandSegment) -> Style {
         Stain::Other => Style::default().fg(colors::OTHER_COLOR),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 8, None)]
+    #[case(1, 0, None)]
+    #[case(1, 8, Some(colors::GENE_DENSITY_COLORS[0]))]
+    #[case(3, 8, Some(colors::GENE_DENSITY_COLORS[1]))]
+    #[case(8, 8, Some(colors::GENE_DENSITY_COLORS[3]))]
+    fn test_get_gene_density_color(
+        #[case] count: usize,
+        #[case] max_count: usize,
+        #[case] expected: Option<Color>,
+    ) {
+        assert_eq!(get_gene_density_color(count, max_count), expected);
+    }
+}
@@ -0,0 +1,52 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Modifier},
+};
+
+/// Reduce the color intensity of everything drawn in the area. level: percent, 0 to 100.
+/// RGB colors are scaled towards black. Other colors cannot be scaled, and are dimmed by the
+/// terminal instead.
+pub fn dim_area(area: &Rect, buf: &mut Buffer, level: u8) {
+    if level == 0 {
+        return;
+    }
+
+    for y in area.y..area.y + area.height {
+        for x in area.x..area.x + area.width {
+            if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                let (fg, fg_scaled) = dim_color(cell.fg, level);
+                let (bg, bg_scaled) = dim_color(cell.bg, level);
+                cell.set_fg(fg).set_bg(bg);
+                if !(fg_scaled && bg_scaled) && cell.symbol() != " " {
+                    cell.modifier.insert(Modifier::DIM);
+                }
+            }
+        }
+    }
+}
+
+/// The dimmed color, and whether it could be scaled. Reset is left as it is.
+fn dim_color(color: Color, level: u8) -> (Color, bool) {
+    let scale = |c: u8| (c as u16 * (100 - level.min(100)) as u16 / 100) as u8;
+    match color {
+        Color::Rgb(r, g, b) => (Color::Rgb(scale(r), scale(g), scale(b)), true),
+        Color::Reset => (Color::Reset, true),
+        color => (color, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Color::Rgb(200, 100, 0), 50, (Color::Rgb(100, 50, 0), true))]
+    #[case(Color::Rgb(200, 100, 0), 100, (Color::Rgb(0, 0, 0), true))]
+    #[case(Color::Reset, 50, (Color::Reset, true))]
+    #[case(Color::LightRed, 50, (Color::LightRed, false))]
+    fn test_dim_color(#[case] color: Color, #[case] level: u8, #[case] expected: (Color, bool)) {
+        assert_eq!(dim_color(color, level), expected);
+    }
+}
@@ -1,26 +1,68 @@
+use crate::helpers::wrap_text;
+use crate::rendering::colors;
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
 
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
-pub fn render_error(area: &Rect, buf: &mut Buffer, errors: &[String]) {
+/// Render the last errors that fit in the area. Long errors are wrapped.
+/// scroll: number of wrapped lines to scroll back from the newest line.
+/// Arrows in the last column show that there are more lines above or below.
+pub fn render_error(area: &Rect, buf: &mut Buffer, errors: &[String], scroll: usize) {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return;
     }
 
-    // render the last errors that fit in the area
+    // The last column is reserved for the scroll indicators.
+    let lines = get_error_lines(errors, area.width as usize - 1);
+    let height = area.height as usize;
 
-    let index_start = errors.len().saturating_sub(area.height as usize);
-    let index_end = errors.len();
+    let index_end = lines.len() - scroll.min(lines.len().saturating_sub(height));
+    let index_start = index_end.saturating_sub(height);
 
-    if index_start >= index_end {
-        return;
+    for (i, line) in lines[index_start..index_end].iter().enumerate() {
+        buf.set_string(area.x, area.y + i as u16, line, Style::default());
+    }
+
+    let indicator_x = area.x + area.width - 1;
+    let indicator_style = Style::default().fg(colors::MATCH_COLOR);
+    if index_start > 0 {
+        buf.set_string(indicator_x, area.y, "↑", indicator_style);
     }
+    if index_end < lines.len() {
+        buf.set_string(indicator_x, area.y + area.height - 1, "↓", indicator_style);
+    }
+}
+
+/// Errors wrapped to the width, oldest first.
+fn get_error_lines(errors: &[String], width: usize) -> Vec<String> {
+    errors
+        .iter()
+        .flat_map(|error| wrap_text(error, width))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, vec!["efgh↑", "ij   "])]
+    #[case(1, vec!["abcd↑", "efgh↓"])]
+    #[case(2, vec!["xy   ", "abcd↓"])]
+    #[case(10, vec!["xy   ", "abcd↓"])]
+    fn test_render_error(#[case] scroll: usize, #[case] expected: Vec<&str>) {
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(area);
+        let errors = vec!["xy".to_string(), "abcdefghij".to_string()];
+        render_error(&area, &mut buf, &errors, scroll);
 
-    for (i, error) in errors[index_start..index_end].iter().enumerate() {
-        if i >= area.height as usize {
-            break;
+        for (y, expected_row) in expected.iter().enumerate() {
+            let row = (0..area.width)
+                .map(|x| buf[(x, y as u16)].symbol().to_string())
+                .collect::<String>();
+            assert_eq!(&row, expected_row);
         }
-        buf.set_string(area.x, area.y + i as u16, error.clone(), Style::default());
     }
 }
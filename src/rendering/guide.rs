@@ -0,0 +1,59 @@
+use crate::models::{
+    contig::Contig,
+    guide::Guide,
+    window::{OnScreenCoordinate, ViewingWindow},
+};
+use crate::rendering::colors;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render guides as vertical lines across the area.
+/// Lines and labels are only drawn on blank cells, so that they do not hide data.
+pub fn render_guides(
+    area: &Rect,
+    buf: &mut Buffer,
+    window: &ViewingWindow,
+    contig: &Contig,
+    guides: &[Guide],
+) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    let style = Style::default().fg(colors::GUIDE_COLOR);
+
+    for guide in guides.iter().filter(|guide| guide.contig == *contig) {
+        let x = match window.onscreen_x_coordinate(guide.position, area) {
+            OnScreenCoordinate::OnScreen(x) => area.x + x as u16,
+            _ => continue,
+        };
+
+        for y in area.y..area.y + area.height {
+            set_if_blank(buf, x, y, '│', style);
+        }
+
+        if let Some(label) = &guide.label {
+            for (i, c) in label.chars().enumerate() {
+                let label_x = x + 1 + i as u16;
+                if label_x >= area.x + area.width {
+                    break;
+                }
+                set_if_blank(buf, label_x, area.y, c, style);
+            }
+        }
+    }
+}
+
+fn set_if_blank(buf: &mut Buffer, x: u16, y: u16, c: char, style: Style) {
+    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+        if cell.symbol() == " " && cell.bg == Color::Reset {
+            cell.set_char(c).set_style(style);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::rendering::colors;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Paragraph, Widget},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 2;
+
+/// Render the SAM header from line scroll, below a title bar. Record type tags (@SQ, @RG, @PG,
+/// ...) are highlighted.
+pub fn render_header(area: Rect, buf: &mut Buffer, header_text: &str, scroll: usize) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    let n_lines = header_text.lines().count();
+    let title = format!(
+        " SAM header: lines {}-{} of {}. j / k / d / u / g / G to scroll, Esc to close",
+        (scroll + 1).min(n_lines),
+        (scroll + area.height as usize - 1).min(n_lines),
+        n_lines
+    );
+
+    let mut lines = vec![Line::styled(
+        title,
+        Style::default().add_modifier(Modifier::REVERSED),
+    )];
+    lines.extend(
+        header_text
+            .lines()
+            .skip(scroll)
+            .take(area.height as usize - 1)
+            .map(get_header_line),
+    );
+
+    Paragraph::new(Text::from(lines)).render(area, buf);
+}
+
+/// A header line with its record type highlighted. Tabs between fields are drawn as spaces.
+fn get_header_line(line: &str) -> Line<'static> {
+    match line.split_once('\t') {
+        Some((record_type, fields)) if record_type.starts_with('@') => Line::from(vec![
+            Span::styled(
+                record_type.to_string(),
+                Style::default()
+                    .fg(colors::HEADER_RECORD_TYPE_COLOR)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" {}", fields.replace('\t', " "))),
+        ]),
+        _ => Line::raw(line.replace('\t', " ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_header() {
+        let header_text = "@HD\tVN:1.6\tSO:coordinate\n@SQ\tSN:chr1\tLN:248956422\n@PG\tID:bwa";
+        let area = Rect::new(0, 0, 30, 3);
+        let mut buf = Buffer::empty(area);
+        render_header(area, &mut buf, header_text, 1);
+
+        let row = |y: u16| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(0).starts_with(" SAM header: lines 2-3 of 3."));
+        assert!(row(1).starts_with("@SQ SN:chr1 LN:248956422"));
+        assert!(row(2).starts_with("@PG"));
+        assert_eq!(buf[(0, 1)].fg, colors::HEADER_RECORD_TYPE_COLOR);
+    }
+}
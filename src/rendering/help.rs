@@ -20,14 +20,21 @@ pub fn render_help(area: Rect, buf: &mut Buffer) {
  
  |:q|    Quit           |<ESC>|     Switch to normal mode / Close this window
  |:h|    Help           |:|         Switch to command mode
+                        |<Ctrl-P>|  Find commands (type to filter, <Enter> to select)
  
  |h / j / k / l|   Move left / down / up / right
  |y / p|           Move left / right faster
+                   Hold a movement key to pan faster (--no-pan-acceleration to disable)
  |w / b|           Beginning of the next / last exon
  |W / B|           Begining of the next / last gene
  |e / ge|          End of the next / last exon
  |E / gE|          End of the next / last gene
+ |]N / [N|         Next / last assembly gap (:gaps to list gaps on the contig)
+ |]s / [s|         Step forward / back by the tiling step (:step to set it)
+ |n / N|           Next / previous :find match
  |z / o|           Zoom in / out
+ |:fitread|        Zoom to fit the topmost visible read at the middle of the view
+ |[e / ]e|         Scroll messages back / forward
  
  |<num><key>|      Repeat movements. Examples:
      - 5h: Move right by 5 bases
@@ -37,6 +44,28 @@ pub fn render_help(area: Rect, buf: &mut Buffer) {
  |:_pos_|          Go to position on same contig.       Example: :1000
  |:_contig_:_pos_| Go to position on a contig.          Example: 17:7572659
  |:_gene_|         Go to _gene_                         Example: :KRAS
+ |:_contig_:_pos_ _ref_>_alt_|  Go to a variant         Example: :7:140753336 A>T
+ |:find _attr_=_value_|  Find genes by gene_name, transcript_id, or strand. Example: :find transcript_id=NM_000546.6
+                   Pasting a region, gene, or variant in normal mode also goes there.
+ 
+ |:guide [_contig_:]_pos_ [_label_]|  Add a vertical guide line.  |:guide clear| Remove guides
+ |:view save _slot_|  Save the view to slot a or b.  |:view _slot_| Restore it.  |<Tab>| Toggle
+ |:cov agg mean / max / min|    Aggregate coverage of bases sharing a column
+ |:cov clips on / off|          Count soft-clipped bases in coverage (default off)
+ |:set _name_ _value_|          Change a setting. Example: :set sequence_anchor top
+ |:set insertions _mode_|       Draw insertions as collapsed length markers, or hide them
+ |:ins|                         Show the bases inserted after the middle of the view
+ |:set min_mapq _n_|            Hide reads with MAPQ < n. :set mapq_255_meaning unavailable / max
+ |:set read_names on / off|     Show read names before reads at base resolution, when few are visible
+ |:export-pileup _path_|        Write a text pileup of the visible region
+ |:yankseq| or |Y|               Show the visible reference sequence and send it to the clipboard (OSC 52)
+ |C|                            Cycle read coloring: plain, strand tint
+ |f| / |F|                      Show only / hide reads with the (non-reference) base at the middle
+ |:filter clear|                Clear the quick filter set with f / F
+ |:export-cmd|                  Show and copy a tgv command line that reproduces the view
+ |:header|                      Show the SAM header. j / k to scroll, Esc to close
+ |:track dim _track_ _level_|   Dim a track (genes, reads, ...) by 0-100%. Example: :track dim genes 50
+ |:step [_bases_]|              Set the tiling step (default: the visible width)
  ",
         env!("CARGO_PKG_VERSION")
     );
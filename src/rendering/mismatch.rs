@@ -0,0 +1,122 @@
+use crate::models::{
+    alignment::AlignedRead,
+    sequence::{base_matches_reference, Sequence},
+    window::{OnScreenCoordinate, ViewingWindow},
+};
+use crate::rendering::{
+    alignment::{consumes_query, consumes_reference},
+    colors,
+    sequence::{get_base_color, get_base_monochrome_style},
+};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use rust_htslib::bam::record::Cigar;
+
+/// Draw the bases of the read that mismatch the reference over the read, at base resolution.
+pub fn render_mismatches(
+    area: &Rect,
+    buf: &mut Buffer,
+    window: &ViewingWindow,
+    read: &AlignedRead,
+    sequence: &Sequence,
+    monochrome: bool,
+) {
+    let y = match window.onscreen_y_coordinate(read.y, area) {
+        OnScreenCoordinate::OnScreen(y) => y,
+        _ => return,
+    };
+
+    for (position, base) in get_mismatches(read, sequence) {
+        if let OnScreenCoordinate::OnScreen(x) = window.onscreen_x_coordinate(position, area) {
+            buf.set_string(
+                area.x + x as u16,
+                area.y + y as u16,
+                (base as char).to_string(),
+                if monochrome {
+                    get_base_monochrome_style(base as char)
+                } else {
+                    Style::default()
+                        .fg(colors::SEQUENCE_FOREGROUND_COLOR)
+                        .bg(get_base_color(base as char))
+                },
+            );
+        }
+    }
+}
+
+/// Aligned bases that are not consistent with the reference.
+/// Returns 1-based reference positions and read bases.
+/// Positions not covered by the loaded sequence are skipped.
+fn get_mismatches(read: &AlignedRead, sequence: &Sequence) -> Vec<(usize, u8)> {
+    let mut output = Vec::new();
+    let bases = read.read.seq().as_bytes();
+
+    let mut reference_pivot = read.start;
+    let mut query_pivot = 0;
+
+    for op in read.read.cigar().iter() {
+        if let Cigar::Match(l) | Cigar::Equal(l) | Cigar::Diff(l) = op {
+            for i in 0..*l as usize {
+                let position = reference_pivot + i;
+                // Reads without a stored sequence (SEQ *) have no bases to compare.
+                let base = match bases.get(query_pivot + i) {
+                    Some(base) => *base,
+                    None => break,
+                };
+                if let Some(reference_base) = sequence.base_at(position) {
+                    if !base_matches_reference(base, reference_base) {
+                        output.push((position, base));
+                    }
+                }
+            }
+        }
+
+        if consumes_reference(op) {
+            reference_pivot += op.len() as usize;
+        }
+        if consumes_query(op) {
+            query_pivot += op.len() as usize;
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{alignment::ClipLengths, contig::Contig};
+    use rust_htslib::bam::record::{CigarString, Record};
+
+    fn get_test_read(sequence: &[u8]) -> AlignedRead {
+        // 1S4M at position 10.
+        let mut record = Record::new();
+        let cigar = CigarString(vec![Cigar::SoftClip(1), Cigar::Match(4)]);
+        let qualities = vec![30; sequence.len()];
+        record.set(b"read1", Some(&cigar), sequence, &qualities);
+        record.set_pos(9);
+
+        let clips = ClipLengths::from_cigar(&record.cigar());
+        AlignedRead {
+            end: record.cigar().end_pos() as usize,
+            read: record,
+            start: 10,
+            leading_softclips: clips.leading_softclips,
+            trailing_softclips: clips.trailing_softclips,
+            leading_hardclips: clips.leading_hardclips,
+            trailing_hardclips: clips.trailing_hardclips,
+            y: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_mismatches() {
+        let sequence = Sequence::new(10, "ARGN".to_string(), Contig::chrom("chr1")).unwrap();
+
+        // A over R is a match, T over G is not, anything over N is a match.
+        assert_eq!(
+            get_mismatches(&get_test_read(b"TAATC"), &sequence),
+            vec![(12, b'T')]
+        );
+        assert_eq!(get_mismatches(&get_test_read(b""), &sequence), vec![]); // SEQ *
+    }
+}
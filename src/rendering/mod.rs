@@ -1,19 +1,34 @@
 mod alignment;
 mod colors;
+mod consensus;
 mod console;
 mod coordinate;
 mod coverage;
 mod cytoband;
+mod dim;
 mod error;
+mod guide;
+mod header;
 mod help;
+mod mismatch;
+mod palette;
+mod primer;
 mod sequence;
+mod splash;
 mod track;
-pub use alignment::render_alignment;
+pub use alignment::{render_alignment, render_zoom_hint};
+pub use consensus::render_consensus;
 pub use console::render_console;
 pub use coordinate::render_coordinates;
 pub use coverage::render_coverage;
-pub use cytoband::render_cytobands;
+pub use cytoband::{render_cytobands, render_gene_density};
+pub use dim::dim_area;
 pub use error::render_error;
+pub use guide::render_guides;
+pub use header::render_header;
 pub use help::render_help;
-pub use sequence::{render_sequence, render_sequence_at_2x};
-pub use track::render_track;
+pub use palette::render_palette;
+pub use primer::render_primers;
+pub use sequence::{render_beyond_reference, render_sequence, render_sequence_at_2x};
+pub use splash::render_splash;
+pub use track::GeneTrackRenderer;
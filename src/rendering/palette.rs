@@ -0,0 +1,85 @@
+use crate::models::palette::PaletteRegister;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+use crate::rendering::colors;
+
+const MIN_AREA_WIDTH: u16 = 10;
+const MIN_AREA_HEIGHT: u16 = 4;
+
+const MAX_PALETTE_WIDTH: u16 = 80;
+
+/// Render the command palette as an overlay at the top of the area.
+pub fn render_palette(area: &Rect, buf: &mut Buffer, palette_register: &PaletteRegister) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    let matches = palette_register.matches();
+
+    // Borders + query line + matches
+    let width = area.width.min(MAX_PALETTE_WIDTH);
+    let height = area.height.min(matches.len() as u16 + 3);
+    let palette_area = Rect::new(area.x + (area.width - width) / 2, area.y, width, height);
+
+    Clear.render(palette_area, buf);
+    let block = Block::default().borders(Borders::ALL).title("Commands");
+    let inner = block.inner(palette_area);
+    block.render(palette_area, buf);
+
+    buf.set_stringn(
+        inner.x,
+        inner.y,
+        format!("> {}", palette_register.query()),
+        inner.width as usize,
+        Style::default(),
+    );
+
+    let name_width = matches
+        .iter()
+        .map(|command| command.name.len())
+        .max()
+        .unwrap_or(0);
+
+    let n_rows = inner.height.saturating_sub(1) as usize;
+    // Scroll so that the selected command is visible.
+    let first_row = palette_register
+        .selected()
+        .saturating_sub(n_rows.saturating_sub(1));
+
+    for (i_row, (i_command, command)) in matches
+        .iter()
+        .enumerate()
+        .skip(first_row)
+        .take(n_rows)
+        .enumerate()
+    {
+        let style = if i_command == palette_register.selected() {
+            Style::default()
+                .bg(colors::PALETTE_SELECTED_COLOR)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        let line = match command.arguments {
+            Some(arguments) => format!(
+                ":{:<name_width$}  {} {}",
+                command.name, command.description, arguments
+            ),
+            None => format!(":{:<name_width$}  {}", command.name, command.description),
+        };
+
+        buf.set_stringn(
+            inner.x,
+            inner.y + 1 + i_row as u16,
+            format!("{:<width$}", line, width = inner.width as usize),
+            inner.width as usize,
+            style,
+        );
+    }
+}
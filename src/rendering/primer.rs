@@ -0,0 +1,110 @@
+use crate::models::{
+    contig::Contig,
+    primer::Primer,
+    window::{OnScreenCoordinate, ViewingWindow},
+};
+use crate::rendering::colors;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Modifier},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Shade primer footprints across the area. Blank cells get a background. If dim_reads, cells
+/// with reads are dimmed as well.
+pub fn render_primers(
+    area: &Rect,
+    buf: &mut Buffer,
+    window: &ViewingWindow,
+    contig: &Contig,
+    primers: &[Primer],
+    dim_reads: bool,
+) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    for primer in primers.iter().filter(|primer| primer.contig == *contig) {
+        if let Some((x, length)) = OnScreenCoordinate::onscreen_start_and_length(
+            &window.onscreen_x_coordinate(primer.start, area),
+            &window.onscreen_x_coordinate(primer.end, area),
+            area,
+        ) {
+            for x in area.x + x as u16..area.x + (x + length) as u16 {
+                for y in area.y..area.y + area.height {
+                    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                        if cell.symbol() == " " && cell.bg == Color::Reset {
+                            cell.set_bg(colors::PRIMER_COLOR);
+                        } else if dim_reads {
+                            if cell.bg == colors::MATCH_COLOR {
+                                cell.set_bg(colors::PRIMER_DIMMED_READ_COLOR);
+                            }
+                            cell.modifier.insert(Modifier::DIM);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_render_primers() {
+        let area = Rect::new(0, 0, 10, 2);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "-----", Style::default().bg(colors::MATCH_COLOR));
+
+        let contig = Contig::contig("MN908947.3");
+        let window = ViewingWindow::new_basewise_window(contig.clone(), 1, 0);
+        let primers = vec![
+            Primer {
+                contig: contig.clone(),
+                start: 4,
+                end: 7,
+                name: None,
+            },
+            Primer {
+                contig: Contig::contig("other"),
+                start: 1,
+                end: 10,
+                name: None,
+            },
+        ];
+
+        render_primers(&area, &mut buf, &window, &contig, &primers, true);
+
+        for x in 0..area.width {
+            let in_primer = (3..7).contains(&x);
+            let read = &buf[(x, 0)];
+            let blank = &buf[(x, 1)];
+
+            if x < 5 {
+                assert_eq!(read.modifier.contains(Modifier::DIM), in_primer);
+                assert_eq!(
+                    read.bg,
+                    if in_primer {
+                        colors::PRIMER_DIMMED_READ_COLOR
+                    } else {
+                        colors::MATCH_COLOR
+                    }
+                );
+            }
+            assert_eq!(
+                blank.bg,
+                if in_primer {
+                    colors::PRIMER_COLOR
+                } else {
+                    Color::Reset
+                }
+            );
+        }
+    }
+}
@@ -1,15 +1,64 @@
 use crate::models::region::Region;
 use crate::models::sequence::Sequence;
+use crate::models::track::Gene;
+use crate::models::window::{OnScreenCoordinate, ViewingWindow};
 use crate::rendering::colors;
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
+pub fn get_base_color(base: char) -> Color {
+    match base {
+        'A' | 'a' => colors::BASE_A,
+        'C' | 'c' => colors::BASE_C,
+        'G' | 'g' => colors::BASE_G,
+        'T' | 't' => colors::BASE_T,
+        _ => colors::BASE_N,
+    }
+}
+
+/// Text attributes that tell bases apart without color (--monochrome or NO_COLOR).
+pub fn get_base_monochrome_style(base: char) -> Style {
+    let modifier = match base {
+        'A' | 'a' => Modifier::BOLD,
+        'C' | 'c' => Modifier::UNDERLINED,
+        'G' | 'g' => Modifier::REVERSED,
+        'T' | 't' => Modifier::BOLD | Modifier::UNDERLINED,
+        _ => Modifier::DIM,
+    };
+    Style::default().add_modifier(modifier)
+}
+
+/// Darker shade of the base color, for every other codon in a CDS.
+fn get_codon_band_color(base: char) -> Color {
+    match base {
+        'A' | 'a' => colors::CODON_BAND_A,
+        'C' | 'c' => colors::CODON_BAND_C,
+        'G' | 'g' => colors::CODON_BAND_G,
+        'T' | 't' => colors::CODON_BAND_T,
+        _ => colors::CODON_BAND_N,
+    }
+}
+
+/// Render the reference sequence in the first row.
+/// If a gene is provided, bases in its CDS are tinted in alternating bands per codon to show the
+/// reading frame.
+/// If a haplotype is provided, it is rendered in the second row. Bases identical to the reference
+/// are dimmed so that differences stand out.
+/// If monochrome, bases are told apart by text attributes instead of colors, and haplotype bases
+/// identical to the reference are drawn as '.'.
 pub fn render_sequence(
     area: &Rect,
     buf: &mut Buffer,
     region: &Region,
     sequence: &Sequence,
+    haplotype: Option<&Sequence>,
+    gene: Option<&Gene>,
+    monochrome: bool,
 ) -> Result<(), ()> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return Ok(());
@@ -17,14 +66,21 @@ pub fn render_sequence(
 
     let sequence_string = sequence.get_sequence(region).ok_or(())?;
 
-    for i in 0..sequence_string.len() {
-        let base = sequence_string.chars().nth(i).unwrap();
-        let color = match base {
-            'A' | 'a' => colors::BASE_A,
-            'C' | 'c' => colors::BASE_C,
-            'G' | 'g' => colors::BASE_G,
-            'T' | 't' => colors::BASE_T,
-            _ => colors::BASE_N,
+    for (i, base) in sequence_string.chars().enumerate() {
+        if monochrome {
+            buf.set_string(
+                area.x + i as u16,
+                area.y,
+                base.to_string(),
+                get_base_monochrome_style(base),
+            );
+            continue;
+        }
+
+        let codon_index = gene.and_then(|gene| gene.codon_index_at(region.start + i));
+        let background = match codon_index {
+            Some(codon_index) if codon_index % 2 == 1 => get_codon_band_color(base),
+            _ => get_base_color(base),
         };
 
         buf.set_string(
@@ -33,18 +89,113 @@ pub fn render_sequence(
             base.to_string(),
             Style::default()
                 .fg(colors::SEQUENCE_FOREGROUND_COLOR)
-                .bg(color),
+                .bg(background),
         );
     }
 
+    if let Some(haplotype) = haplotype {
+        if area.height < 2 {
+            return Ok(());
+        }
+        // The haplotype may not cover the region, e.g. beyond the end of its contig.
+        let haplotype_string = match haplotype.get_sequence(region) {
+            Some(haplotype_string) => haplotype_string,
+            None => return Ok(()),
+        };
+
+        for (i, (base, reference_base)) in haplotype_string
+            .chars()
+            .zip(sequence_string.chars())
+            .enumerate()
+        {
+            let (symbol, style) = match (base.eq_ignore_ascii_case(&reference_base), monochrome) {
+                (true, true) => (".".to_string(), Style::default()),
+                (false, true) => (base.to_string(), get_base_monochrome_style(base)),
+                (true, false) => (base.to_string(), Style::default().fg(colors::MATCH_COLOR)),
+                (false, false) => (
+                    base.to_string(),
+                    Style::default()
+                        .fg(colors::SEQUENCE_FOREGROUND_COLOR)
+                        .bg(get_base_color(base))
+                        .add_modifier(Modifier::BOLD),
+                ),
+            };
+
+            buf.set_string(area.x + i as u16, area.y + 1, symbol, style);
+        }
+    }
+
     Ok(())
 }
 
+/// Mark the part of the area beyond the end of the reference contig, e.g. when the BAM header
+/// reports a longer contig than the reference genome. Reads there have no reference sequence.
+/// reference_length: 1-based, inclusive.
+pub fn render_beyond_reference(
+    area: &Rect,
+    buf: &mut Buffer,
+    window: &ViewingWindow,
+    reference_length: usize,
+) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    let x_start = match window.onscreen_x_coordinate(reference_length + 1, area) {
+        OnScreenCoordinate::Left(_) => 0,
+        OnScreenCoordinate::OnScreen(x) => x as u16,
+        OnScreenCoordinate::Right(_) => return,
+    };
+
+    let style = Style::default()
+        .fg(colors::SEQUENCE_FOREGROUND_COLOR)
+        .bg(colors::BEYOND_REFERENCE_COLOR);
+    for y in area.y..area.y + area.height {
+        buf.set_string(
+            area.x + x_start,
+            y,
+            "/".repeat((area.width - x_start) as usize),
+            style,
+        );
+    }
+
+    let label = format!(" beyond reference end ({} bp) ", reference_length);
+    if label.len() <= (area.width - x_start) as usize {
+        buf.set_string(area.x + x_start, area.y, label, style);
+    }
+}
+
+/// Render two bases per cell. If a haplotype is provided, it is rendered in the second row the
+/// same way.
+/// If monochrome, only the first base of each cell is shown, with its text attributes.
 pub fn render_sequence_at_2x(
     area: &Rect,
     buf: &mut Buffer,
     region: &Region,
     sequence: &Sequence,
+    haplotype: Option<&Sequence>,
+    monochrome: bool,
+) -> Result<(), ()> {
+    render_sequence_row_at_2x(area.x, area.y, buf, region, sequence, monochrome)?;
+
+    if let Some(haplotype) = haplotype {
+        if area.height >= 2 {
+            // The haplotype may not cover the region. Leave the row blank then.
+            let _ =
+                render_sequence_row_at_2x(area.x, area.y + 1, buf, region, haplotype, monochrome);
+        }
+    }
+
+    Ok(())
+}
+
+fn render_sequence_row_at_2x(
+    x: u16,
+    y: u16,
+    buf: &mut Buffer,
+    region: &Region,
+    sequence: &Sequence,
+    monochrome: bool,
 ) -> Result<(), ()> {
     let sequence_string = sequence.get_sequence(region).ok_or(())?;
 
@@ -52,29 +203,90 @@ pub fn render_sequence_at_2x(
         let base_1 = sequence_string.chars().nth(i * 2).unwrap();
         let base_2 = sequence_string.chars().nth(i * 2 + 1).unwrap();
 
-        let color_character = match base_1 {
-            'A' | 'a' => colors::BASE_A,
-            'C' | 'c' => colors::BASE_C,
-            'G' | 'g' => colors::BASE_G,
-            'T' | 't' => colors::BASE_T,
-            _ => colors::BASE_N,
-        };
-
-        let color_background = match base_2 {
-            'A' | 'a' => colors::BASE_A,
-            'C' | 'c' => colors::BASE_C,
-            'G' | 'g' => colors::BASE_G,
-            'T' | 't' => colors::BASE_T,
-            _ => colors::BASE_N,
-        };
+        if monochrome {
+            buf.set_string(
+                x + i as u16,
+                y,
+                base_1.to_string(),
+                get_base_monochrome_style(base_1),
+            );
+            continue;
+        }
 
         buf.set_string(
-            area.x + i as u16,
-            area.y,
+            x + i as u16,
+            y,
             "▌",
-            Style::default().fg(color_character).bg(color_background),
+            Style::default()
+                .fg(get_base_color(base_1))
+                .bg(get_base_color(base_2)),
         );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::contig::Contig;
+
+    #[test]
+    fn test_monochrome_bases_are_distinguishable() {
+        let contig = Contig::contig("chr1");
+        let sequence = Sequence::new(1, "ACGTN".to_string(), contig.clone()).unwrap();
+        let haplotype = Sequence::new(1, "ACCTN".to_string(), contig.clone()).unwrap();
+        let region = Region {
+            contig,
+            start: 1,
+            end: 5,
+        };
+
+        let area = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(area);
+        render_sequence(
+            &area,
+            &mut buf,
+            &region,
+            &sequence,
+            Some(&haplotype),
+            None,
+            true,
+        )
+        .unwrap();
+
+        // No colors, and a distinct attribute per base.
+        let modifiers = (0..5)
+            .map(|x| {
+                let cell = &buf[(x, 0)];
+                assert_eq!((cell.fg, cell.bg), (Color::Reset, Color::Reset));
+                cell.modifier
+            })
+            .collect::<Vec<Modifier>>();
+        for i in 0..modifiers.len() {
+            for j in i + 1..modifiers.len() {
+                assert_ne!(modifiers[i], modifiers[j]);
+            }
+        }
+
+        // Only the haplotype difference is shown.
+        let haplotype_row = (0..5)
+            .map(|x| buf[(x, 1)].symbol().to_string())
+            .collect::<String>();
+        assert_eq!(haplotype_row, "..C..");
+        assert_eq!(buf[(2, 1)].modifier, Modifier::UNDERLINED);
+    }
+
+    #[test]
+    fn test_render_beyond_reference() {
+        let area = Rect::new(0, 0, 40, 2);
+        let mut buf = Buffer::empty(area);
+        let window = ViewingWindow::new_basewise_window(Contig::contig("chrM"), 1, 0);
+        render_beyond_reference(&area, &mut buf, &window, 10);
+
+        assert_eq!(buf[(9, 0)].bg, Color::Reset);
+        assert_eq!(buf[(10, 0)].bg, colors::BEYOND_REFERENCE_COLOR);
+        assert_eq!(buf[(11, 0)].symbol(), "b");
+        assert_eq!(buf[(39, 1)].symbol(), "/");
+    }
+}
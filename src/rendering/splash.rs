@@ -0,0 +1,27 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render a loading screen, shown before the initial region is loaded.
+pub fn render_splash(area: Rect, buf: &mut Buffer) {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return;
+    }
+
+    let lines = [
+        format!("Terminal Genome Viewer {}", env!("CARGO_PKG_VERSION")),
+        "Loading…".to_string(),
+    ];
+
+    let y_start = area.y + area.height.saturating_sub(lines.len() as u16) / 2;
+
+    for (i, line) in lines.iter().enumerate() {
+        let y = y_start + i as u16;
+        if y >= area.y + area.height {
+            break;
+        }
+        let x = area.x + area.width.saturating_sub(line.chars().count() as u16) / 2;
+        buf.set_stringn(x, y, line, area.width as usize, Style::default());
+    }
+}
@@ -1,15 +1,18 @@
+use crate::models::view::TrackKind;
 use crate::models::{
+    data::Data,
     reference::Reference,
     strand::Strand,
     track::{FeatureType, Gene, Track},
     window::{OnScreenCoordinate, ViewingWindow},
 };
-use crate::traits::GenomeInterval;
+use crate::traits::{GenomeInterval, TrackRenderer};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{palette::tailwind, Color, Style},
 };
+use std::cell::RefCell;
 
 const MIN_AREA_WIDTH: u16 = 5;
 const MIN_AREA_HEIGHT: u16 = 2;
@@ -17,12 +20,132 @@ const MIN_AREA_HEIGHT: u16 = 2;
 // Type alias for the complex return type
 type TrackRenderInfo = (usize, String, Style, Option<(usize, String)>);
 
+/// The gene track, registered by default.
+pub struct GeneTrackRenderer {
+    pub reference: Option<Reference>,
+
+    /// Genes near the last rendered window. Redraws reuse it instead of scanning all genes.
+    nearby_genes: RefCell<Option<NearbyGenes>>,
+}
+
+impl GeneTrackRenderer {
+    pub fn new(reference: Option<Reference>) -> Self {
+        Self {
+            reference,
+            nearby_genes: RefCell::new(None),
+        }
+    }
+}
+
+/// Indexes of the genes overlapping a region around the viewing window.
+struct NearbyGenes {
+    /// Identifies the track the indexes point into: contig, number of genes, and the span of the
+    /// genes. A reloaded track invalidates the indexes.
+    track_key: (String, usize, usize, usize),
+
+    /// 1-based, inclusive.
+    start: usize,
+    end: usize,
+
+    indexes: Vec<usize>,
+}
+
+impl NearbyGenes {
+    /// The region is extended by this many window widths on both sides, so that panning within
+    /// it does not rescan the track.
+    const MARGIN_IN_WINDOWS: usize = 1;
+
+    fn track_key(track: &Track) -> (String, usize, usize, usize) {
+        (
+            track.contig.full_name(),
+            track.genes.len(),
+            track.genes.first().map(|gene| gene.start()).unwrap_or(0),
+            track.genes.last().map(|gene| gene.end()).unwrap_or(0),
+        )
+    }
+
+    fn new(track: &Track, start: usize, end: usize) -> Self {
+        let margin = (end + 1 - start) * Self::MARGIN_IN_WINDOWS;
+        let start = start.saturating_sub(margin).max(1);
+        let end = end + margin;
+
+        Self {
+            track_key: Self::track_key(track),
+            start,
+            end,
+            indexes: track
+                .genes
+                .iter()
+                .enumerate()
+                .filter(|(_, gene)| gene.start() <= end && gene.end() >= start)
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+
+    /// Whether the cached genes can be used to render start-end of the track.
+    fn covers(&self, track: &Track, start: usize, end: usize) -> bool {
+        self.track_key == Self::track_key(track)
+            && covers_window((self.start, self.end), (start, end))
+    }
+}
+
+/// Whether the cached region contains the window, and is not much wider than needed after
+/// zooming in.
+fn covers_window(cached: (usize, usize), window: (usize, usize)) -> bool {
+    let window_width = window.1 + 1 - window.0;
+    let cached_width = cached.1 + 1 - cached.0;
+
+    cached.0 <= window.0
+        && cached.1 >= window.1
+        && cached_width <= window_width * (2 * NearbyGenes::MARGIN_IN_WINDOWS + 1) * 2
+}
+
+impl TrackRenderer for GeneTrackRenderer {
+    fn height(&self) -> u16 {
+        2
+    }
+
+    fn kind(&self) -> Option<TrackKind> {
+        Some(TrackKind::Genes)
+    }
+
+    fn render(&self, area: &Rect, buf: &mut Buffer, window: &ViewingWindow, data: &Data) {
+        if self.reference.is_none() {
+            return;
+        }
+
+        let track = match &data.track {
+            Some(track) => track,
+            None => return,
+        };
+
+        let (start, end) = (window.left(), window.right(area));
+        let mut nearby_genes = self.nearby_genes.borrow_mut();
+        if !nearby_genes
+            .as_ref()
+            .is_some_and(|nearby_genes| nearby_genes.covers(track, start, end))
+        {
+            *nearby_genes = Some(NearbyGenes::new(track, start, end));
+        }
+
+        if let Some(nearby_genes) = nearby_genes.as_ref() {
+            let genes = nearby_genes
+                .indexes
+                .iter()
+                .map(|i| &track.genes[*i])
+                .collect::<Vec<&Gene>>();
+            render_track(area, buf, window, &genes, self.reference.as_ref());
+        }
+    }
+}
+
 /// Render the genome features.
 pub fn render_track(
     area: &Rect,
     buf: &mut Buffer,
     window: &ViewingWindow,
-    track: &Track,
+    genes: &[&Gene],
     _reference: Option<&Reference>,
 ) {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
@@ -30,7 +153,7 @@ pub fn render_track(
     }
 
     let mut right_most_label_onscreen_x = 0;
-    for feature in track.genes.iter() {
+    for feature in genes.iter() {
         for (track_x, track_string, track_style, label_info) in
             get_rendering_info(window, area, feature)
         {
@@ -88,7 +211,16 @@ fn get_rendering_info(window: &ViewingWindow, area: &Rect, gene: &Gene) -> Vec<T
         let mut non_cds_exons_info: Vec<TrackRenderInfo> = Vec::new();
         let mut introns_info: Vec<TrackRenderInfo> = Vec::new();
         let mut right_most_label_onscreen_x = 0;
-        for (feature_start, feature_end, feature_type, feature_index) in gene.features() {
+        let features = gene.features();
+
+        // Exons split into UTR and CDS pieces are labeled on the CDS piece only.
+        let coding_exon_numbers = features
+            .iter()
+            .filter(|(_, _, feature_type, _)| *feature_type == FeatureType::Exon)
+            .map(|(_, _, _, exon_number)| *exon_number)
+            .collect::<Vec<usize>>();
+
+        for (feature_start, feature_end, feature_type, feature_index) in features {
             let feature_start_x = window.onscreen_x_coordinate(feature_start, area);
             let feature_end_x = window.onscreen_x_coordinate(feature_end, area);
 
@@ -105,7 +237,7 @@ fn get_rendering_info(window: &ViewingWindow, area: &Rect, gene: &Gene) -> Vec<T
 
                 match feature_type {
                     FeatureType::Exon => {
-                        let label = format!("{}:exon{}", gene.name, feature_index);
+                        let label = get_exon_label(&gene.name, feature_index, length);
 
                         let label_x = x + (length.saturating_sub(label.len()) / 2);
                         let label_right_coordinate = label_x + label.len() - 1; // inclusive
@@ -124,7 +256,7 @@ fn get_rendering_info(window: &ViewingWindow, area: &Rect, gene: &Gene) -> Vec<T
                         ));
                     }
                     FeatureType::NonCDSExon => {
-                        let label = gene.name.to_string();
+                        let label = get_exon_label(&gene.name, feature_index, length);
                         let label_x = x + (length.saturating_sub(label.len()) / 2);
                         let label_right_coordinate = label_x + label.len() - 1; // inclusive
 
@@ -132,7 +264,9 @@ fn get_rendering_info(window: &ViewingWindow, area: &Rect, gene: &Gene) -> Vec<T
                             x,
                             string,
                             style,
-                            if label_x > right_most_label_onscreen_x + 1 {
+                            if !coding_exon_numbers.contains(&feature_index)
+                                && label_x > right_most_label_onscreen_x + 1
+                            {
                                 right_most_label_onscreen_x = label_right_coordinate;
 
                                 Some((label_x, label))
@@ -155,6 +289,17 @@ fn get_rendering_info(window: &ViewingWindow, area: &Rect, gene: &Gene) -> Vec<T
     }
 }
 
+/// Exon label that fits in the exon's onscreen length, from the most to the least verbose.
+/// Falls back to the most verbose label when none fits.
+fn get_exon_label(gene_name: &str, exon_number: usize, length: usize) -> String {
+    let full_label = format!("{}:exon{}", gene_name, exon_number);
+
+    [format!("exon{}", exon_number), exon_number.to_string()]
+        .into_iter()
+        .find(|label| full_label.len() > length && label.len() <= length)
+        .unwrap_or(full_label)
+}
+
 const EXON_ARROW_GAP: usize = 5;
 const INTRON_ARROW_GAP: usize = 10;
 const GENE_ARROW_GAP: usize = 5;
@@ -212,3 +357,24 @@ fn get_feature_segment_string_and_style(
 
     (string, style)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case((1, 300), (101, 200), true)]
+    #[case((1, 300), (151, 250), true)] // panned within the margin
+    #[case((1, 300), (251, 350), false)] // panned out of the cached region
+    #[case((1, 300), (1, 600), false)] // zoomed out
+    #[case((1, 300), (101, 150), true)] // zoomed in twice
+    #[case((1, 300), (101, 125), false)] // zoomed in further: rescan a smaller region
+    fn test_covers_window(
+        #[case] cached: (usize, usize),
+        #[case] window: (usize, usize),
+        #[case] expected: bool,
+    ) {
+        assert_eq!(covers_window(cached, window), expected);
+    }
+}
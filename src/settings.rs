@@ -1,7 +1,14 @@
 use crate::error::TGVError;
-use crate::helpers::is_url;
-use crate::models::{message::StateMessage, reference::Reference};
-use clap::Parser;
+use crate::helpers::{is_url, shell_quote};
+use crate::models::{
+    alignment::{InsertionDisplay, Mapq255Meaning, ReadColoring, ReadFilter},
+    coverage::{CoverageAggregation, CoverageBaseline, LibraryType},
+    message::StateMessage,
+    reference::Reference,
+    sequence::SequenceAnchor,
+    variant::VariantLocus,
+};
+use clap::{Parser, ValueEnum};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -16,7 +23,8 @@ pub struct Cli {
     #[arg(short = 'i', long = "index", value_name = "PATH", default_value = "")]
     index: String,
 
-    /// Starting region. Supported formats: [chr]:[pos] (e.g. 12:25398142); [gene] (e.g. TP53).
+    /// Starting region. Supported formats: [chr]:[pos] (e.g. 12:25398142); [gene] (e.g. TP53);
+    /// [chr]:[pos] [ref]>[alt] (e.g. "7:140753336 A>T").
     /// If not provided, TGV will find a default starting region.
     #[arg(short = 'r', long = "region", default_value = "")]
     region: String,
@@ -30,6 +38,122 @@ pub struct Cli {
     #[arg(long)]
     no_reference: bool,
 
+    /// Second haplotype FASTA file, e.g. for phased or personalized references.
+    /// Must be indexed (with .fai file in the same directory). Displayed below the reference
+    /// sequence, with bases differing from the reference highlighted. Requires a reference.
+    #[arg(long = "haplotype", value_name = "FASTA")]
+    haplotype_path: Option<String>,
+
+    /// Primer BED file for amplicon sequencing. Primer footprints are shaded in the alignment area,
+    /// since primer-derived bases should not be trusted for variant calling.
+    #[arg(long = "primers", value_name = "BED")]
+    primer_path: Option<String>,
+
+    /// Also dim reads within primer footprints. Requires --primers.
+    #[arg(long)]
+    dim_primer_reads: bool,
+
+    /// UCSC chain file from the viewed build to another build. A second ruler shows the positions
+    /// in the other build, with gaps where positions do not map.
+    #[arg(long = "chain", value_name = "PATH")]
+    chain_path: Option<String>,
+
+    /// Soft memory budget (in MB) for loaded alignments.
+    /// When the budget is approached, reads are downsampled and cached regions are shrunk instead of
+    /// allocating unbounded memory. This trades completeness of the pileup for stability under
+    /// memory pressure. Coverage is still computed from all reads.
+    #[arg(long = "max-memory", value_name = "MB")]
+    max_memory_mb: Option<usize>,
+
+    /// Download the index of a remote (http / https) BAM file again, instead of using the cached
+    /// copy. Cached indexes are otherwise revalidated with the server on each launch.
+    #[arg(long)]
+    refresh_cache: bool,
+
+    /// Where coverage bars grow from.
+    /// middle: bars grow from a zero line, leaving room for negative values below it.
+    #[arg(long, value_enum, default_value_t = CoverageBaseline::Bottom)]
+    coverage_baseline: CoverageBaseline,
+
+    /// How coverage is aggregated when multiple bases are drawn in one column.
+    /// max: spikes are not missed. min: dropouts are not missed.
+    #[arg(long, value_enum, default_value_t = CoverageAggregation::Mean)]
+    coverage_bin_agg: CoverageAggregation,
+
+    /// Show a consensus row of the reads below the coverage, at base resolution. Heterozygous sites
+    /// are shown as IUPAC ambiguity codes.
+    #[arg(long)]
+    consensus: bool,
+
+    /// Minimum depth of a consensus base. Columns with lower depth are shown as gaps.
+    #[arg(long, value_name = "DEPTH", default_value_t = 4)]
+    consensus_min_depth: usize,
+
+    /// Minimum share (in percent) of the second most common base for the consensus to show an
+    /// IUPAC ambiguity code instead of the majority base.
+    #[arg(long, value_name = "PERCENT", default_value_t = 20)]
+    consensus_allele_percent: u8,
+
+    /// Where the reference sequence row is placed.
+    /// top: directly above the reads. bottom: below the reads, next to the gene track.
+    #[arg(long, value_enum, default_value_t = SequenceAnchor::Bottom)]
+    sequence_anchor: SequenceAnchor,
+
+    /// Hide reads with a lower mapping quality. 0: show all reads.
+    #[arg(long, value_name = "MAPQ", default_value_t = 0)]
+    min_mapq: u8,
+
+    /// What a MAPQ of 255 means. unavailable (SAM spec): such reads are hidden by --min-mapq.
+    /// max (some aligners): such reads pass any --min-mapq.
+    #[arg(long = "mapq-255", value_enum, default_value_t = Mapq255Meaning::Unavailable)]
+    mapq_255_meaning: Mapq255Meaning,
+
+    /// Show read names in the empty space before reads at base resolution. Names are hidden when
+    /// many reads are visible.
+    #[arg(long)]
+    read_names: bool,
+
+    /// What reads are colored by. strand-tint: a faint background by strand, keeping bases
+    /// readable. Cycled with C.
+    #[arg(long = "color-by", value_enum, default_value_t = ReadColoring::Plain)]
+    read_coloring: ReadColoring,
+
+    /// How insertions are drawn at base resolution.
+    /// collapsed: a marker with the insertion length. hidden: not drawn.
+    #[arg(long, value_enum, default_value_t = InsertionDisplay::Collapsed)]
+    insertions: InsertionDisplay,
+
+    /// Strandedness of an RNA-seq library.
+    /// For stranded libraries, coverage is split by the transcript strand inferred from read flags:
+    /// forward-strand coverage above a zero line and reverse-strand coverage below it.
+    #[arg(long, value_enum, default_value_t = LibraryType::Unstranded)]
+    library_type: LibraryType,
+
+    /// Tell bases apart by text attributes (bold, underline, ...) instead of colors in the sequence
+    /// and mismatches. Also enabled by the NO_COLOR environment variable.
+    #[arg(long)]
+    monochrome: bool,
+
+    /// Do not speed up panning while a movement key is held down.
+    #[arg(long)]
+    no_pan_acceleration: bool,
+
+    /// Stop redrawing while the terminal window is unfocused, and resume when it is focused again.
+    /// Has no effect on terminals that do not report focus changes.
+    #[arg(long)]
+    pause_on_unfocus: bool,
+
+    /// Read control messages from a file, typically a named pipe created with mkfifo.
+    /// Each line is a JSON object, e.g. {"goto": "chr1:1000"}, {"command": "guide 1000"}, or
+    /// {"set": {"coverage_bin_agg": "max"}}. A regular file is read once.
+    #[arg(long = "control", value_name = "PATH")]
+    control_path: Option<String>,
+
+    /// Append a record of each jump, command, and export to a file, one JSON object per line,
+    /// with a timestamp and the resulting region.
+    #[arg(long = "audit", value_name = "PATH")]
+    audit_path: Option<String>,
+
     /// For development purposes only
     /// Display messages in the terminal.
     #[arg(long)]
@@ -44,6 +168,60 @@ pub struct Settings {
     // pub bed_path: Option<String>,
     pub reference: Option<Reference>,
 
+    pub haplotype_path: Option<String>,
+
+    pub primer_path: Option<String>,
+    pub dim_primer_reads: bool,
+
+    /// Chain file for the secondary ruler.
+    pub chain_path: Option<String>,
+
+    pub max_memory_mb: Option<usize>,
+
+    /// Ignore cached indexes of remote BAM files.
+    pub refresh_cache: bool,
+
+    pub coverage_baseline: CoverageBaseline,
+
+    pub coverage_bin_agg: CoverageAggregation,
+
+    /// Count soft-clipped bases in coverage. Toggled with :cov clips on|off.
+    pub coverage_include_clips: bool,
+
+    /// Consensus row (--consensus).
+    pub consensus: bool,
+    pub consensus_min_depth: usize,
+    pub consensus_allele_percent: u8,
+
+    pub sequence_anchor: SequenceAnchor,
+
+    /// Changed with :set insertions.
+    pub insertions: InsertionDisplay,
+
+    /// Changed with :set min_mapq and :set mapq_255_meaning.
+    pub min_mapq: u8,
+    pub mapq_255_meaning: Mapq255Meaning,
+
+    /// Changed with :set read_names.
+    pub read_names: bool,
+
+    /// Cycled with C.
+    pub read_coloring: ReadColoring,
+
+    pub library_type: LibraryType,
+
+    /// Tell bases apart without colors.
+    pub monochrome: bool,
+
+    pub pan_acceleration: bool,
+
+    pub pause_on_unfocus: bool,
+
+    pub control_path: Option<String>,
+
+    /// Audit log (JSONL) of the session.
+    pub audit_path: Option<String>,
+
     pub initial_state_messages: Vec<StateMessage>,
 
     pub test_mode: bool,
@@ -124,18 +302,170 @@ impl Settings {
             ));
         }
 
+        // 4. A haplotype is displayed against the reference
+        if cli.haplotype_path.is_some() && reference.is_none() {
+            return Err(TGVError::CliError(
+                "A reference is required to display a haplotype".to_string(),
+            ));
+        }
+
+        // 5. Dimming primer reads requires primers
+        if cli.dim_primer_reads && cli.primer_path.is_none() {
+            return Err(TGVError::CliError(
+                "--dim-primer-reads requires --primers".to_string(),
+            ));
+        }
+
+        // 6. The second most common base cannot make up more than half of the depth
+        if cli.consensus_allele_percent == 0 || cli.consensus_allele_percent > 50 {
+            return Err(TGVError::CliError(
+                "--consensus-allele-percent must be between 1 and 50".to_string(),
+            ));
+        }
+
         Ok(Self {
             bam_path,
             bai_path,
             // vcf_path,
             // bed_path,
             reference,
+            haplotype_path: cli.haplotype_path,
+            primer_path: cli.primer_path,
+            dim_primer_reads: cli.dim_primer_reads,
+            chain_path: cli.chain_path,
+            max_memory_mb: cli.max_memory_mb,
+            refresh_cache: cli.refresh_cache,
+            coverage_baseline: cli.coverage_baseline,
+            coverage_bin_agg: cli.coverage_bin_agg,
+            coverage_include_clips: false,
+            consensus: cli.consensus,
+            consensus_min_depth: cli.consensus_min_depth,
+            consensus_allele_percent: cli.consensus_allele_percent,
+            sequence_anchor: cli.sequence_anchor,
+            insertions: cli.insertions,
+            min_mapq: cli.min_mapq,
+            mapq_255_meaning: cli.mapq_255_meaning,
+            read_names: cli.read_names,
+            read_coloring: cli.read_coloring,
+            library_type: cli.library_type,
+            monochrome: cli.monochrome,
+            pan_acceleration: !cli.no_pan_acceleration,
+            pause_on_unfocus: cli.pause_on_unfocus,
+            control_path: cli.control_path,
+            audit_path: cli.audit_path,
             initial_state_messages,
             test_mode,
             debug: cli.debug,
         })
     }
 
+    pub fn read_filter(&self) -> ReadFilter {
+        ReadFilter {
+            min_mapq: self.min_mapq,
+            mapq_255_meaning: self.mapq_255_meaning,
+            base: None,
+        }
+    }
+
+    /// A tgv command line that opens the same files with the same settings, starting at the
+    /// region (if provided). Options at their default values are left out. The control and audit
+    /// paths and --refresh-cache belong to a session and are left out too.
+    pub fn command_line(&self, region: Option<&str>) -> String {
+        let defaults = Cli::parse_from(["tgv"]);
+        let mut words = vec!["tgv".to_string()];
+        let mut option = |name: &str, value: Option<String>| {
+            words.push(name.to_string());
+            words.extend(value.map(|value| shell_quote(&value)));
+        };
+
+        if let Some(bai_path) = &self.bai_path {
+            option("-i", Some(bai_path.clone()));
+        }
+        if let Some(region) = region {
+            option("-r", Some(region.to_string()));
+        }
+        match self.reference {
+            None => option("--no-reference", None),
+            Some(reference) if reference.to_string() != defaults.reference => {
+                option("-g", Some(reference.to_string()))
+            }
+            Some(_) => {}
+        }
+        if let Some(haplotype_path) = &self.haplotype_path {
+            option("--haplotype", Some(haplotype_path.clone()));
+        }
+        if let Some(primer_path) = &self.primer_path {
+            option("--primers", Some(primer_path.clone()));
+        }
+        if self.dim_primer_reads {
+            option("--dim-primer-reads", None);
+        }
+        if let Some(chain_path) = &self.chain_path {
+            option("--chain", Some(chain_path.clone()));
+        }
+        if let Some(max_memory_mb) = self.max_memory_mb {
+            option("--max-memory", Some(max_memory_mb.to_string()));
+        }
+        if self.coverage_baseline != defaults.coverage_baseline {
+            option("--coverage-baseline", value_name(&self.coverage_baseline));
+        }
+        if self.coverage_bin_agg != defaults.coverage_bin_agg {
+            option("--coverage-bin-agg", value_name(&self.coverage_bin_agg));
+        }
+        if self.consensus {
+            option("--consensus", None);
+        }
+        if self.consensus_min_depth != defaults.consensus_min_depth {
+            option(
+                "--consensus-min-depth",
+                Some(self.consensus_min_depth.to_string()),
+            );
+        }
+        if self.consensus_allele_percent != defaults.consensus_allele_percent {
+            option(
+                "--consensus-allele-percent",
+                Some(self.consensus_allele_percent.to_string()),
+            );
+        }
+        if self.sequence_anchor != defaults.sequence_anchor {
+            option("--sequence-anchor", value_name(&self.sequence_anchor));
+        }
+        if self.min_mapq != defaults.min_mapq {
+            option("--min-mapq", Some(self.min_mapq.to_string()));
+        }
+        if self.mapq_255_meaning != defaults.mapq_255_meaning {
+            option("--mapq-255", value_name(&self.mapq_255_meaning));
+        }
+        if self.read_names {
+            option("--read-names", None);
+        }
+        if self.read_coloring != defaults.read_coloring {
+            option("--color-by", value_name(&self.read_coloring));
+        }
+        if self.insertions != defaults.insertions {
+            option("--insertions", value_name(&self.insertions));
+        }
+        if self.library_type != defaults.library_type {
+            option("--library-type", value_name(&self.library_type));
+        }
+        if self.monochrome {
+            option("--monochrome", None);
+        }
+        if !self.pan_acceleration {
+            option("--no-pan-acceleration", None);
+        }
+        if self.pause_on_unfocus {
+            option("--pause-on-unfocus", None);
+        }
+
+        // The BAM path is positional.
+        if let Some(bam_path) = &self.bam_path {
+            words.insert(1, shell_quote(bam_path));
+        }
+
+        words.join(" ")
+    }
+
     fn translate_initial_state_messages(
         region_string: &str,
         _reference: Option<&Reference>,
@@ -147,6 +477,11 @@ impl Settings {
             return Ok(vec![StateMessage::GoToDefault]);
         }
 
+        // Interpretation 2: variant (contig:position ref>alt)
+        if let Some(variant) = VariantLocus::parse(region_string) {
+            return Ok(vec![StateMessage::GoToVariant(variant)]);
+        }
+
         // Check format
         let split = region_string.split(":").collect::<Vec<&str>>();
         if split.len() > 2 {
@@ -156,7 +491,7 @@ impl Settings {
             )));
         }
 
-        // Interpretation 2: genome:position
+        // Interpretation 3: genome:position
         if split.len() == 2 {
             match split[1].parse::<usize>() {
                 Ok(n) => {
@@ -174,11 +509,18 @@ impl Settings {
             }
         }
 
-        // Interpretation 3: gene name
+        // Interpretation 4: gene name
         Ok(vec![StateMessage::GoToGene(region_string.to_string())])
     }
 }
 
+/// Name of a value as typed on the command line.
+fn value_name<T: ValueEnum>(value: &T) -> Option<String> {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,59 +529,155 @@ mod tests {
     use crate::models::reference::Reference;
     use rstest::rstest;
 
+    /// Settings of "tgv input.bam". Cases override the fields that differ.
+    fn base_settings() -> Settings {
+        Settings {
+            bam_path: Some("input.bam".to_string()),
+            bai_path: None,
+            reference: Some(Reference::Hg38),
+            haplotype_path: None,
+            primer_path: None,
+            dim_primer_reads: false,
+            chain_path: None,
+            max_memory_mb: None,
+            refresh_cache: false,
+            coverage_baseline: CoverageBaseline::Bottom,
+            coverage_bin_agg: CoverageAggregation::Mean,
+            coverage_include_clips: false,
+            consensus: false,
+            consensus_min_depth: 4,
+            consensus_allele_percent: 20,
+            sequence_anchor: SequenceAnchor::Bottom,
+            insertions: InsertionDisplay::Collapsed,
+            min_mapq: 0,
+            mapq_255_meaning: Mapq255Meaning::Unavailable,
+            read_names: false,
+            read_coloring: ReadColoring::Plain,
+            library_type: LibraryType::Unstranded,
+            monochrome: false,
+            pan_acceleration: true,
+            pause_on_unfocus: false,
+            control_path: None,
+            audit_path: None,
+            initial_state_messages: vec![StateMessage::GoToDefault],
+            test_mode: false,
+            debug: false,
+        }
+    }
+
     #[rstest]
     #[case("tgv", Ok(Settings {
         bam_path: None,
-        bai_path: None,
-        reference: Some(Reference::Hg38),
-        initial_state_messages: vec![StateMessage::GoToDefault],
-        test_mode: false,
-        debug: false,
+        ..base_settings()
     }))] // empty input: no bam file and no reference: browse hg38
-    #[case("tgv input.bam", Ok(Settings {
-        bam_path: Some("input.bam".to_string()),
-        bai_path: None,
-        reference: Some(Reference::Hg38),
-        initial_state_messages: vec![StateMessage::GoToDefault],
-        test_mode: false,
-        debug: false,
-    }))]
+    #[case("tgv input.bam", Ok(base_settings()))]
     #[case("tgv wrong.extension", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.bam -r chr1:12345", Ok(Settings {
-        bam_path: Some("input.bam".to_string()),
-        bai_path: None,
-        reference: Some(Reference::Hg38),
         initial_state_messages: vec![StateMessage::GotoContigCoordinate("chr1".to_string(), 12345)],
-        test_mode: false,
-        debug: false,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam -r \"chr7:140753336 A>T\"", Ok(Settings {
+        initial_state_messages: vec![StateMessage::GoToVariant(VariantLocus {
+            contig: "chr7".to_string(),
+            position: 140753336,
+            reference_allele: "A".to_string(),
+            alternative_allele: "T".to_string(),
+        })],
+        ..base_settings()
     }))]
     #[case("tgv input.bam -r chr1:invalid", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.bam -r chr1:12:12345", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.bam -r TP53", Ok(Settings {
-        bam_path: Some("input.bam".to_string()),
-        bai_path: None,
-        reference: Some(Reference::Hg38),
         initial_state_messages: vec![StateMessage::GoToGene("TP53".to_string())],
-        test_mode: false,
-        debug: false,
+        ..base_settings()
     }))]
     #[case("tgv input.bam -r TP53 -g hg19", Ok(Settings {
-        bam_path: Some("input.bam".to_string()),
-        bai_path: None,
         reference: Some(Reference::Hg19),
         initial_state_messages: vec![StateMessage::GoToGene("TP53".to_string())],
-        test_mode: false,
-        debug: false,
+        ..base_settings()
     }))]
     #[case("tgv input.bam -r TP53 -g hg100", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.bam -r 1:12345 --no-reference", Ok(Settings {
-        bam_path: Some("input.bam".to_string()),
-        bai_path: None,
         reference: None,
         initial_state_messages: vec![StateMessage::GotoContigCoordinate("1".to_string(), 12345)],
-        test_mode: false,
-        debug: false,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --max-memory 512", Ok(Settings {
+        max_memory_mb: Some(512),
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --chain hg38ToHg19.over.chain", Ok(Settings {
+        chain_path: Some("hg38ToHg19.over.chain".to_string()),
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --coverage-baseline middle", Ok(Settings {
+        coverage_baseline: CoverageBaseline::Middle,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --haplotype hap2.fa", Ok(Settings {
+        haplotype_path: Some("hap2.fa".to_string()),
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --library-type fr-firststrand", Ok(Settings {
+        library_type: LibraryType::FrFirststrand,
+        ..base_settings()
     }))]
+    #[case("tgv input.bam --no-pan-acceleration", Ok(Settings {
+        pan_acceleration: false,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --coverage-bin-agg max", Ok(Settings {
+        coverage_bin_agg: CoverageAggregation::Max,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --sequence-anchor top", Ok(Settings {
+        sequence_anchor: SequenceAnchor::Top,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --insertions hidden", Ok(Settings {
+        insertions: InsertionDisplay::Hidden,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --min-mapq 20 --mapq-255 max", Ok(Settings {
+        min_mapq: 20,
+        mapq_255_meaning: Mapq255Meaning::Max,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --read-names", Ok(Settings {
+        read_names: true,
+        ..base_settings()
+    }))]
+    #[case("tgv https://example.com/input.bam --refresh-cache", Ok(Settings {
+        bam_path: Some("https://example.com/input.bam".to_string()),
+        refresh_cache: true,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --color-by strand-tint", Ok(Settings {
+        read_coloring: ReadColoring::StrandTint,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --control tgv.fifo", Ok(Settings {
+        control_path: Some("tgv.fifo".to_string()),
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --monochrome", Ok(Settings {
+        monochrome: true,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --consensus --consensus-min-depth 10 --consensus-allele-percent 30", Ok(Settings {
+        consensus: true,
+        consensus_min_depth: 10,
+        consensus_allele_percent: 30,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --consensus --consensus-allele-percent 60", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --primers primers.bed --dim-primer-reads", Ok(Settings {
+        primer_path: Some("primers.bed".to_string()),
+        dim_primer_reads: true,
+        ..base_settings()
+    }))]
+    #[case("tgv input.bam --dim-primer-reads", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --haplotype hap2.fa --no-reference", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.bam -r TP53 -g hg19 --no-reference", Err(TGVError::CliError("".to_string())))]
     #[case("tgv --no-reference", Err(TGVError::CliError("".to_string())))]
     #[case("tgv -r 1:12345", Err(TGVError::CliError("".to_string())))]
@@ -256,4 +694,27 @@ mod tests {
             _ => panic!("Unexpected test result"),
         }
     }
+
+    #[rstest]
+    #[case("tgv", None)]
+    #[case(
+        "tgv input.bam -i input.bai -r 12:25398142 -g hg19 --coverage-bin-agg max --consensus \
+         --min-mapq 20 --mapq-255 max --read-names --color-by strand-tint --insertions hidden \
+         --monochrome",
+        Some("12:25398142")
+    )]
+    #[case(
+        "tgv 'my input.bam' -r '7:140753336 A>T' --no-reference --chain hg38ToHg19.over.chain \
+         --consensus-min-depth 10 --library-type fr-firststrand --no-pan-acceleration",
+        Some("7:140753336 A>T")
+    )]
+    fn test_command_line(#[case] command_line: &str, #[case] region: Option<&str>) {
+        let command_line = command_line
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let settings =
+            Settings::new(Cli::parse_from(shlex::split(&command_line).unwrap()), false).unwrap();
+        assert_eq!(settings.command_line(region), command_line);
+    }
 }
@@ -1,29 +1,47 @@
+use crate::audit::{audit_event, AuditLog};
+use crate::control::parse_control_message;
 use crate::error::TGVError;
-use crate::helpers::is_url;
+use crate::helpers::{osc52_copy_sequence, wrap_text};
 use crate::models::{
+    acceleration::PanAccelerator,
+    alignment::{open_indexed_reader, Alignment, BaseFilter, ReadFilter},
+    consensus::count_bases,
     contig::Contig,
     cytoband::Cytoband,
     data::Data,
+    find::{FeatureMatch, FeatureMatches, FeatureQuery},
+    gap::GapIndex,
+    guide::Guide,
     message::{DataMessage, StateMessage},
     mode::InputMode,
+    palette::PaletteRegister,
+    pileup::format_pileup,
     reference::Reference,
     region::Region,
     register::{CommandModeRegister, NormalModeRegister},
+    services::indexes::IndexCacheService,
+    variant::VariantLocus,
+    view::{DisplaySettings, SavedView, TrackKind, ViewSlot},
     window::ViewingWindow,
 };
 use crate::settings::Settings;
 use crate::traits::GenomeInterval;
-use crossterm::event::{KeyCode, KeyEvent};
+use clap::ValueEnum;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Rect;
-use rust_htslib::bam::{self, IndexedReader, Read};
+use rust_htslib::bam::{self, Read};
 use std::collections::HashMap;
-use url::Url;
+use std::io::Write;
+use std::time::Instant;
 /// A collection of contigs. This helps relative contig movements.
 struct ContigCollection {
     contigs: Vec<Contig>,
     contig_lengths: Vec<Option<usize>>,
 
     contig_index: HashMap<String, usize>,
+
+    /// SAM header text of the BAM file, for :header.
+    header_text: Option<String>,
 }
 
 impl ContigCollection {
@@ -44,6 +62,7 @@ impl ContigCollection {
             contigs,
             contig_lengths,
             contig_index,
+            header_text: None,
         })
     }
 
@@ -61,29 +80,7 @@ impl ContigCollection {
         bai_path: Option<&String>,
         reference: Option<&Reference>,
     ) -> Result<Self, TGVError> {
-        // Use the indexed_reader::Builder pattern as shown in alignment.rs
-        let is_remote_path = is_url(path);
-        let bam = match bai_path {
-            Some(bai_path) => {
-                if is_remote_path {
-                    return Err(TGVError::IOError(
-                        "Custom .bai path for remote BAM files are not supported yet.".to_string(),
-                    ));
-                }
-                IndexedReader::from_path_and_index(path, bai_path)
-                    .map_err(|e| TGVError::IOError(e.to_string()))?
-            }
-            None => {
-                if is_remote_path {
-                    IndexedReader::from_url(
-                        &Url::parse(path).map_err(|e| TGVError::IOError(e.to_string()))?,
-                    )
-                    .unwrap()
-                } else {
-                    IndexedReader::from_path(path).map_err(|e| TGVError::IOError(e.to_string()))?
-                }
-            }
-        };
+        let bam = open_indexed_reader(path, bai_path)?;
 
         let header = bam::Header::from_template(bam.header());
 
@@ -111,7 +108,9 @@ impl ContigCollection {
             }
         }
 
-        Self::new(contigs, contig_lengths)
+        let mut collection = Self::new(contigs, contig_lengths)?;
+        collection.header_text = Some(String::from_utf8_lossy(bam.header().as_bytes()).to_string());
+        Ok(collection)
     }
 
     pub fn contains(&self, contig: &Contig) -> bool {
@@ -158,6 +157,10 @@ pub struct State {
     // Registers
     normal_mode_register: NormalModeRegister,
     command_mode_register: CommandModeRegister,
+    palette_register: PaletteRegister,
+
+    /// Speeds up panning while a movement key is held down.
+    pan_accelerator: PanAccelerator,
 
     /// Settings
     pub settings: Settings,
@@ -165,17 +168,70 @@ pub struct State {
     /// Error messages for display.
     pub errors: Vec<String>,
 
+    /// Number of wrapped error lines scrolled back from the newest.
+    error_scroll: usize,
+
+    /// First line of the SAM header shown by :header.
+    header_scroll: usize,
+
     /// Cytobands
     cytobands: Option<Vec<Cytoband>>,
+
+    /// Vertical guide lines.
+    guides: Vec<Guide>,
+
+    /// Views saved for quick A/B switching.
+    saved_views: HashMap<ViewSlot, SavedView>,
+    current_view: Option<ViewSlot>,
+
+    /// Variant navigated to, whose reference allele is checked once sequences are loaded.
+    pending_variant: Option<VariantLocus>,
+
+    /// Audit log of the session (--audit).
+    audit: Option<AuditLog>,
+
+    /// Dim levels of tracks, in percent. Tracks not listed are not dimmed.
+    track_dims: HashMap<TrackKind, u8>,
+
+    /// Matches of the last :find, cycled with n / N.
+    feature_matches: Option<FeatureMatches>,
+
+    /// Tiling: step in bases and the left coordinate of the first tile.
+    tile_step: Option<usize>,
+    tile_origin: usize,
+
+    /// Quick filter by the base at one position (f / F), and the contig it applies to.
+    quick_filter: Option<(Contig, BaseFilter)>,
 }
 
 /// Basics
 impl State {
     pub async fn new(settings: Settings) -> Result<Self, TGVError> {
+        // Indexes of remote BAM files are cached between sessions. If caching fails, htslib
+        // downloads the index itself.
+        let mut bai_path = settings.bai_path.clone();
+        let mut index_cache_error = None;
+        if let (Some(bam_path), None) = (settings.bam_path.as_ref(), bai_path.as_ref()) {
+            if IndexCacheService::supports(bam_path) {
+                let index_path = match IndexCacheService::new() {
+                    Ok(service) => {
+                        service
+                            .get_index_path(bam_path, settings.refresh_cache)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                match index_path {
+                    Ok(index_path) => bai_path = Some(index_path),
+                    Err(e) => index_cache_error = Some(e),
+                }
+            }
+        }
+
         let contigs = match settings.bam_path.clone() {
             Some(bam_path) => Some(ContigCollection::from_bam(
                 &bam_path,
-                settings.bai_path.as_ref(),
+                bai_path.as_ref(),
                 settings.reference.as_ref(),
             )?),
             None => None,
@@ -186,9 +242,15 @@ impl State {
             None => None,
         };
 
-        let data = Data::new(&settings).await?;
+        let mut data = Data::new(&settings).await?;
+        data.bai_path = bai_path;
 
-        Ok(Self {
+        let audit = match settings.audit_path.as_ref() {
+            Some(audit_path) => Some(AuditLog::open(audit_path)?),
+            None => None,
+        };
+
+        let mut state = Self {
             window: None,
             input_mode: InputMode::Normal,
             exit: false,
@@ -197,12 +259,31 @@ impl State {
 
             normal_mode_register: NormalModeRegister::new(),
             command_mode_register: CommandModeRegister::new(),
+            palette_register: PaletteRegister::new(),
+            pan_accelerator: PanAccelerator::new(),
 
             contigs,
             settings,
             cytobands,
             errors: Vec::new(),
-        })
+            error_scroll: 0,
+            header_scroll: 0,
+            guides: Vec::new(),
+            saved_views: HashMap::new(),
+            current_view: None,
+            pending_variant: None,
+            audit,
+            track_dims: HashMap::new(),
+            feature_matches: None,
+            tile_step: None,
+            tile_origin: 1,
+            quick_filter: None,
+        };
+
+        if let Some(e) = index_cache_error {
+            state.add_error_message(e);
+        }
+        Ok(state)
     }
 
     pub fn update_frame_area(&mut self, area: Rect) {
@@ -281,6 +362,11 @@ impl State {
         &self.command_mode_register
     }
 
+    /// Reference to the command palette register.
+    pub fn palette_register(&self) -> &PaletteRegister {
+        &self.palette_register
+    }
+
     pub fn initialized(&self) -> bool {
         self.window.is_some()
     }
@@ -309,14 +395,174 @@ impl State {
         }
     }
 
+    pub fn error_scroll(&self) -> usize {
+        self.error_scroll
+    }
+
+    /// Lines scrolled by d / u in the :header overlay.
+    const HEADER_PAGE: usize = 20;
+
+    /// SAM header text of the loaded BAM file.
+    pub fn header_text(&self) -> Option<&str> {
+        self.contigs
+            .as_ref()
+            .and_then(|contigs| contigs.header_text.as_deref())
+    }
+
+    pub fn header_scroll(&self) -> usize {
+        self.header_scroll
+    }
+
+    /// Scroll the :header overlay. The last line stays on screen.
+    fn scroll_header(&mut self, lines: isize) {
+        let n_lines = self
+            .header_text()
+            .map_or(0, |header| header.lines().count());
+        self.header_scroll = self
+            .header_scroll
+            .saturating_add_signed(lines)
+            .min(n_lines.saturating_sub(1));
+    }
+
+    /// Scroll the error area back (positive) or forward (negative) by wrapped lines.
+    /// Errors are wrapped to the frame width, minus the column of scroll indicators.
+    fn scroll_errors(&mut self, lines: isize) {
+        let width = self
+            .area
+            .map_or(0, |area| (area.width as usize).saturating_sub(1));
+        let n_lines = self
+            .errors
+            .iter()
+            .map(|error| wrap_text(error, width).len())
+            .sum::<usize>();
+
+        self.error_scroll = self
+            .error_scroll
+            .saturating_add_signed(lines)
+            .min(n_lines.saturating_sub(1));
+    }
+
     pub fn cytobands(&self) -> Option<&[Cytoband]> {
         self.cytobands.as_deref()
     }
+
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    /// Dim level of a track, in percent.
+    pub fn track_dim(&self, track: TrackKind) -> u8 {
+        self.track_dims.get(&track).copied().unwrap_or(0)
+    }
+
+    /// Interpret a contig name typed by the user. Check that it is in the BAM header if provided.
+    fn resolve_contig(&self, contig: &str) -> Result<Contig, TGVError> {
+        let contig = match self.settings.reference {
+            Some(Reference::Hg38) | Some(Reference::Hg19) => Contig::chrom(contig),
+            _ => Contig::contig(contig),
+        };
+        if let Some(contigs) = &self.contigs {
+            if !contigs.contains(&contig) {
+                return Err(TGVError::StateError(format!(
+                    "Contig {} not found in the BAM header",
+                    contig.full_name()
+                )));
+            }
+        }
+        Ok(contig)
+    }
 }
 
 impl State {
     pub async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), TGVError> {
         let messages = self.translate_key_event(key_event);
+        let messages = self.accelerate_pan(key_event.code, messages);
+        self.handle(messages).await
+    }
+
+    /// Scale left / right movements up while the key is held down.
+    fn accelerate_pan(&mut self, key: KeyCode, messages: Vec<StateMessage>) -> Vec<StateMessage> {
+        let is_pan = messages
+            .iter()
+            .any(|m| matches!(m, StateMessage::MoveLeft(_) | StateMessage::MoveRight(_)));
+
+        if !self.settings.pan_acceleration || !is_pan {
+            self.pan_accelerator.reset();
+            return messages;
+        }
+
+        let factor = self.pan_accelerator.register(key, Instant::now());
+
+        messages
+            .into_iter()
+            .map(|m| match m {
+                StateMessage::MoveLeft(n) => StateMessage::MoveLeft(n * factor),
+                StateMessage::MoveRight(n) => StateMessage::MoveRight(n * factor),
+                m => m,
+            })
+            .collect()
+    }
+
+    /// Handle pasted text.
+    /// In command mode and the command palette, the text is typed into the input. In normal mode,
+    /// a pasted region, gene, or variant (e.g. "chr7:140753336 A>T") is navigated to directly.
+    /// Other text is rejected.
+    pub async fn handle_paste(&mut self, text: String) -> Result<(), TGVError> {
+        let text = text.trim();
+        let messages: Vec<StateMessage> = match self.input_mode {
+            InputMode::Command => text
+                .chars()
+                .filter(|c| !c.is_control())
+                .map(StateMessage::AddCharToCommandModeRegisters)
+                .collect(),
+            InputMode::Palette => text
+                .chars()
+                .filter(|c| !c.is_control())
+                .map(StateMessage::AddCharToPaletteRegister)
+                .collect(),
+            InputMode::Normal => match CommandModeRegister::parse_paste(text) {
+                Ok(messages) => messages,
+                Err(error_message) => {
+                    vec![StateMessage::Error(TGVError::ParsingError(error_message))]
+                }
+            },
+            _ => vec![StateMessage::Error(TGVError::ParsingError(format!(
+                "Cannot navigate to pasted text: {}",
+                text
+            )))],
+        };
+
+        if self.settings.reference.is_none()
+            && messages.iter().any(|message| message.requires_reference())
+        {
+            return self
+                .handle(vec![StateMessage::Error(TGVError::StateError(
+                    "Reference is not provided".to_string(),
+                ))])
+                .await;
+        }
+
+        self.handle(messages).await
+    }
+
+    /// Handle a control message from an external tool (--control).
+    /// Malformed messages are reported as errors.
+    pub async fn handle_control_message(&mut self, line: &str) -> Result<(), TGVError> {
+        let messages = match parse_control_message(line) {
+            Ok(messages) => messages,
+            Err(error_message) => vec![StateMessage::Error(TGVError::ParsingError(error_message))],
+        };
+
+        if self.settings.reference.is_none()
+            && messages.iter().any(|message| message.requires_reference())
+        {
+            return self
+                .handle(vec![StateMessage::Error(TGVError::StateError(
+                    "Reference is not provided".to_string(),
+                ))])
+                .await;
+        }
+
         self.handle(messages).await
     }
 
@@ -334,6 +580,13 @@ impl State {
 
     /// Handle messages.
     pub async fn handle(&mut self, messages: Vec<StateMessage>) -> Result<(), TGVError> {
+        let n_errors = self.errors.len();
+
+        let audited_messages = messages
+            .iter()
+            .filter_map(|m| audit_event(m).map(|event| (event, format!("{:?}", m))))
+            .collect::<Vec<(&str, String)>>();
+
         let debug_messages_0 = messages
             .iter()
             .map(|m| format!("{:?}", m))
@@ -349,6 +602,24 @@ impl State {
 
         let loaded_data = self.data.handle_data_messages(data_messages).await?;
 
+        if let Some(warning) = self.data.memory_warning.take() {
+            self.errors.push(warning);
+        }
+        if let Some(warning) = self.data.haplotype_warning.take() {
+            self.errors.push(warning);
+        }
+
+        if let Some(variant) = self.pending_variant.take() {
+            self.check_variant_reference_allele(&variant)?;
+        }
+
+        self.record_audit(audited_messages);
+
+        // Show new errors.
+        if self.errors.len() > n_errors {
+            self.error_scroll = 0;
+        }
+
         if self.settings.debug {
             if loaded_data {
                 self.errors.push(format!(
@@ -378,6 +649,16 @@ impl State {
                         StateMessage::SwitchMode(InputMode::Command),
                         StateMessage::ClearNormalModeRegisters,
                     ],
+                    KeyCode::Tab => vec![
+                        StateMessage::ToggleView,
+                        StateMessage::ClearNormalModeRegisters,
+                    ],
+                    KeyCode::Char('p') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                        vec![
+                            StateMessage::SwitchMode(InputMode::Palette),
+                            StateMessage::ClearNormalModeRegisters,
+                        ]
+                    }
                     _ => match self.normal_mode_register.translate(key_event.code) {
                         Ok(messages) => messages,
                         Err(error_message) => vec![
@@ -412,10 +693,30 @@ impl State {
                     }
                 },
             },
+            InputMode::Palette => match self.palette_register.translate(key_event.code) {
+                Ok(messages) => messages,
+                Err(error_message) => vec![StateMessage::CommandModeRegisterError(error_message)],
+            },
             InputMode::Help => match key_event.code {
                 KeyCode::Esc => vec![StateMessage::SwitchMode(InputMode::Normal)],
                 _ => vec![],
             },
+            InputMode::Header => match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    vec![StateMessage::SwitchMode(InputMode::Normal)]
+                }
+                KeyCode::Char('j') | KeyCode::Down => vec![StateMessage::ScrollHeader(1)],
+                KeyCode::Char('k') | KeyCode::Up => vec![StateMessage::ScrollHeader(-1)],
+                KeyCode::Char('d') | KeyCode::PageDown => {
+                    vec![StateMessage::ScrollHeader(Self::HEADER_PAGE as isize)]
+                }
+                KeyCode::Char('u') | KeyCode::PageUp => {
+                    vec![StateMessage::ScrollHeader(-(Self::HEADER_PAGE as isize))]
+                }
+                KeyCode::Char('g') => vec![StateMessage::ScrollHeader(isize::MIN)],
+                KeyCode::Char('G') => vec![StateMessage::ScrollHeader(isize::MAX)],
+                _ => vec![],
+            },
         };
 
         // Check that if there is a message that requires the reference genome, make sure it is provided.
@@ -475,6 +776,13 @@ impl State {
                 self.command_mode_register.move_cursor_right(amount)
             }
 
+            // Command palette handling
+            StateMessage::AddCharToPaletteRegister(c) => self.palette_register.add_char(c),
+            StateMessage::BackspacePaletteRegister => self.palette_register.backspace(),
+            StateMessage::ClearPaletteRegister => self.palette_register.clear(),
+            StateMessage::SelectNextPaletteCommand => self.palette_register.select_next(),
+            StateMessage::SelectPreviousPaletteCommand => self.palette_register.select_previous(),
+
             // Normal mode handling
             StateMessage::AddCharToNormalModeRegisters(c) => self.normal_mode_register.add_char(c),
             StateMessage::NormalModeRegisterError(error_message) => {
@@ -495,6 +803,7 @@ impl State {
             // Zoom handling
             StateMessage::ZoomOut(r) => data_messages.extend(self.handle_zoom_out(r)?),
             StateMessage::ZoomIn(r) => data_messages.extend(self.handle_zoom_in(r)?),
+            StateMessage::FitRead => data_messages.extend(self.fit_read()?),
 
             // Relative feature movement handling
             StateMessage::GotoNextExonsStart(_)
@@ -510,18 +819,144 @@ impl State {
                 data_messages.extend(self.handle_gene_movement_message(message).await?);
             }
 
+            // Tiling
+            StateMessage::SetTileStep(step) => self.set_tile_step(step)?,
+            StateMessage::StepForward(n) => data_messages.extend(self.step_tiles(n as isize)?),
+            StateMessage::StepBackward(n) => data_messages.extend(self.step_tiles(-(n as isize))?),
+
+            // Assembly gaps
+            StateMessage::GotoNextGap(_) | StateMessage::GotoPreviousGap(_) => {
+                data_messages.extend(self.handle_gap_movement_message(message).await?);
+            }
+            StateMessage::ListGaps => match self.list_gaps().await {
+                Ok(summary) => self.errors.push(summary),
+                Err(e) => self.add_error_message(e),
+            },
+
             // Absolute feature handling
             StateMessage::GoToGene(_) => {
                 data_messages.extend(self.handle_goto_feature_message(message).await?);
             }
+            StateMessage::GoToVariant(variant) => {
+                data_messages.extend(self.handle_goto_variant_message(variant)?);
+            }
+
+            // Feature search
+            StateMessage::FindFeatures(query) => {
+                data_messages.extend(self.find_features(query).await?);
+            }
+            StateMessage::GotoNextMatch(n) => {
+                data_messages.extend(self.cycle_feature_matches(n as isize)?);
+            }
+            StateMessage::GotoPreviousMatch(n) => {
+                data_messages.extend(self.cycle_feature_matches(-(n as isize))?);
+            }
 
             // Find the default region
             StateMessage::GoToDefault => {
                 data_messages.extend(self.handle_goto_default_message().await?);
             }
 
+            // Saved views
+            StateMessage::SaveView(slot) => match self.save_view(slot) {
+                Ok(()) => self.errors.push(format!("View saved to slot {}", slot)),
+                Err(e) => self.add_error_message(e),
+            },
+            StateMessage::RestoreView(slot) => {
+                data_messages.extend(self.restore_view(slot)?);
+            }
+            StateMessage::ToggleView => {
+                let slot = self.current_view.map_or(ViewSlot::A, |slot| slot.other());
+                data_messages.extend(self.restore_view(slot)?);
+            }
+
+            // Guides
+            StateMessage::AddGuide(contig, position, label) => {
+                match self.add_guide(contig, position, label) {
+                    Ok(()) => {}
+                    Err(e) => self.add_error_message(e),
+                }
+            }
+            StateMessage::ClearGuides => self.guides.clear(),
+
+            StateMessage::SetCoverageBaseline(baseline) => {
+                self.settings.coverage_baseline = baseline
+            }
+            StateMessage::SetSequenceAnchor(anchor) => self.settings.sequence_anchor = anchor,
+            StateMessage::SetInsertionDisplay(insertions) => self.settings.insertions = insertions,
+            StateMessage::SetMinMapq(min_mapq) => {
+                self.settings.min_mapq = min_mapq;
+                self.push_read_filter_message();
+            }
+            StateMessage::SetMapq255Meaning(meaning) => {
+                self.settings.mapq_255_meaning = meaning;
+                self.push_read_filter_message();
+            }
+            StateMessage::SetReadNames(read_names) => self.settings.read_names = read_names,
+            StateMessage::QuickFilter(carries) => match self.set_quick_filter(carries) {
+                Ok(()) => {}
+                Err(e) => self.add_error_message(e),
+            },
+            StateMessage::ClearQuickFilter => {
+                self.quick_filter = None;
+                self.errors.push("Cleared the quick filter".to_string());
+            }
+            StateMessage::CycleReadColoring => {
+                self.settings.read_coloring = self.settings.read_coloring.next();
+                self.errors
+                    .push(format!("Color reads by: {}", self.settings.read_coloring));
+            }
+            StateMessage::SetTrackDim(track, level) => {
+                self.track_dims.insert(track, level);
+            }
+            StateMessage::SetCoverageAggregation(aggregation) => {
+                self.settings.coverage_bin_agg = aggregation;
+                self.errors
+                    .push(format!("Coverage aggregation: {}", aggregation));
+            }
+            StateMessage::SetCoverageClips(include_clips) => {
+                self.settings.coverage_include_clips = include_clips;
+                self.errors.push(format!(
+                    "Soft-clipped bases in coverage: {}",
+                    if include_clips { "on" } else { "off" }
+                ));
+            }
+
+            StateMessage::ExportPileup(path) => match self.export_pileup(&path) {
+                Ok(()) => self.errors.push(format!("Pileup exported to {}", path)),
+                Err(e) => self.add_error_message(e),
+            },
+            StateMessage::YankSequence => {
+                if let Err(e) = self.yank_sequence() {
+                    self.add_error_message(e);
+                }
+            }
+            StateMessage::ExportCommandLine => {
+                if let Err(e) = self.export_command_line() {
+                    self.add_error_message(e);
+                }
+            }
+
             // Error messages
             StateMessage::Error(e) => self.add_error_message(e),
+            StateMessage::ScrollErrorsBack(n) => self.scroll_errors(n as isize),
+            StateMessage::ScrollErrorsForward(n) => self.scroll_errors(-(n as isize)),
+
+            StateMessage::ShowHeader => match self.header_text() {
+                Some(_) => {
+                    self.header_scroll = 0;
+                    self.input_mode = InputMode::Header;
+                }
+                None => self
+                    .add_error_message(TGVError::StateError("No BAM file is loaded".to_string())),
+            },
+            StateMessage::ScrollHeader(lines) => self.scroll_header(lines),
+
+            StateMessage::ShowInsertions => {
+                if let Err(e) = self.show_insertions() {
+                    self.add_error_message(e);
+                }
+            }
 
             // Others
             _ => {}
@@ -560,14 +995,23 @@ impl State {
                 data_messages.push(DataMessage::RequiresCompleteFeatures(track_cache_region));
             }
 
-            if (viewing_window.zoom() <= Self::MAX_ZOOM_TO_DISPLAY_SEQUENCES)
-                && !self.data.has_complete_sequence(&viewing_region)
-            {
-                let sequence_cache_region = self.sequence_cache_region(&viewing_region)?;
-                data_messages.push(DataMessage::RequiresCompleteSequences(
-                    sequence_cache_region,
+            if !self.data.has_gene_density(&viewing_window.contig) {
+                data_messages.push(DataMessage::RequiresGeneDensity(
+                    viewing_window.contig.clone(),
                 ));
             }
+
+            // Beyond the reference contig end there is no sequence to load.
+            if let Some(sequence_region) = viewing_region.clamp(self.reference_contig_length()?) {
+                if (viewing_window.zoom() <= Self::MAX_ZOOM_TO_DISPLAY_SEQUENCES)
+                    && !self.data.has_complete_sequence(&sequence_region)
+                {
+                    let sequence_cache_region = self.sequence_cache_region(&sequence_region)?;
+                    data_messages.push(DataMessage::RequiresCompleteSequences(
+                        sequence_cache_region,
+                    ));
+                }
+            }
         }
 
         Ok(data_messages)
@@ -576,67 +1020,31 @@ impl State {
     const ALIGNMENT_CACHE_RATIO: usize = 3;
 
     fn alignment_cache_region(&self, region: &Region) -> Result<Region, TGVError> {
-        let left = region
-            .start
-            .saturating_sub(Self::ALIGNMENT_CACHE_RATIO * region.width() / 2)
-            .max(1);
-        let right = region
-            .end
-            .saturating_add(Self::ALIGNMENT_CACHE_RATIO * region.width() / 2)
-            .min(if let Some(contig_length) = self.contig_length()? {
-                contig_length
-            } else {
-                usize::MAX
-            });
-        Ok(Region {
-            contig: region.contig.clone(),
-            start: left,
-            end: right,
-        })
+        // Under memory pressure, only cache the viewing region.
+        let cache_ratio = match self.data.alignment.as_ref() {
+            Some(alignment) if alignment.is_downsampled() => 0,
+            _ => Self::ALIGNMENT_CACHE_RATIO,
+        };
+
+        Ok(region.expand(cache_ratio, self.contig_length()?))
     }
 
     const SEQUENCE_CACHE_RATIO: usize = 3;
 
+    /// Sequences are cached only up to the reference contig end, which can be before the BAM
+    /// header contig end.
     fn sequence_cache_region(&self, region: &Region) -> Result<Region, TGVError> {
-        let left = region
-            .start
-            .saturating_sub(Self::SEQUENCE_CACHE_RATIO * region.width() / 2)
-            .max(1);
-        let right = region
-            .end
-            .saturating_add(Self::SEQUENCE_CACHE_RATIO * region.width() / 2)
-            .min(if let Some(contig_length) = self.contig_length()? {
-                contig_length
-            } else {
-                usize::MAX
-            });
-        Ok(Region {
-            contig: region.contig.clone(),
-            start: left,
-            end: right,
-        })
+        let contig_length = match self.reference_contig_length()? {
+            Some(reference_length) => Some(reference_length),
+            None => self.contig_length()?,
+        };
+        Ok(region.expand(Self::SEQUENCE_CACHE_RATIO, contig_length))
     }
 
     const TRACK_CACHE_RATIO: usize = 10;
 
     fn track_cache_region(&self, region: &Region) -> Result<Region, TGVError> {
-        let left = region
-            .start
-            .saturating_sub(Self::TRACK_CACHE_RATIO * region.width() / 2)
-            .max(1);
-        let right = region
-            .end
-            .saturating_add(Self::TRACK_CACHE_RATIO * region.width() / 2)
-            .min(if let Some(contig_length) = self.contig_length()? {
-                contig_length
-            } else {
-                usize::MAX
-            });
-        Ok(Region {
-            contig: region.contig.clone(),
-            start: left,
-            end: right,
-        })
+        Ok(region.expand(Self::TRACK_CACHE_RATIO, self.contig_length()?))
     }
 }
 
@@ -696,19 +1104,7 @@ impl State {
                 viewing_window.set_middle(&current_frame_area, n, contig_length);
             }
             StateMessage::GotoContigCoordinate(contig, n) => {
-                // If bam_path is provided, check that the contig is valid.
-                let contig = match self.settings.reference {
-                    Some(Reference::Hg38) | Some(Reference::Hg19) => Contig::chrom(&contig),
-                    _ => Contig::contig(&contig),
-                };
-                if let Some(contigs) = &self.contigs {
-                    if !contigs.contains(&contig) {
-                        return Err(TGVError::StateError(format!(
-                            "Contig {} not found in the BAM header",
-                            contig.full_name()
-                        )));
-                    }
-                }
+                let contig = self.resolve_contig(&contig)?;
 
                 let current_frame_area = *self.current_frame_area()?;
 
@@ -756,6 +1152,49 @@ impl State {
         self.get_data_requirements()
     }
 
+    /// Bases shown on each side of a read fit by :fitread.
+    const FIT_READ_PADDING: usize = 5;
+
+    /// Zoom to fit the read at the middle of the view, plus padding. If several reads cover the
+    /// middle, the topmost visible one is fit.
+    fn fit_read(&mut self) -> Result<Vec<DataMessage>, TGVError> {
+        let middle = self.middle()?;
+        let top = self.viewing_window()?.top();
+        let filter = self.read_filter();
+
+        let read = self.data.alignment.as_ref().and_then(|alignment| {
+            alignment
+                .reads
+                .iter()
+                .filter(|read| read.start <= middle && read.end >= middle && read.y >= top)
+                .filter(|read| filter.admits(&read.read))
+                .min_by_key(|read| read.y)
+                .map(|read| (read.start, read.end))
+        });
+        let (start, end) = match read {
+            Some(span) => span,
+            None => {
+                self.add_error_message(TGVError::StateError(format!(
+                    "No reads at the middle of the view ({})",
+                    middle
+                )));
+                return Ok(vec![]);
+            }
+        };
+
+        let contig_length = self.contig_length()?;
+        let current_frame_area = *self.current_frame_area()?;
+        let viewing_window = self.viewing_window_mut()?;
+
+        viewing_window.fit(
+            start.saturating_sub(Self::FIT_READ_PADDING),
+            end + Self::FIT_READ_PADDING,
+            &current_frame_area,
+            contig_length,
+        );
+        self.get_data_requirements()
+    }
+
     /// Maximum length of the contig.
     pub fn contig_length(&self) -> Result<Option<usize>, TGVError> {
         let contig = self.contig()?;
@@ -776,6 +1215,16 @@ impl State {
 
         Ok(None)
     }
+
+    /// Contig length in the reference genome, which can differ from the BAM header.
+    pub fn reference_contig_length(&self) -> Result<Option<usize>, TGVError> {
+        let contig = self.contig()?;
+        Ok(self
+            .settings
+            .reference
+            .as_ref()
+            .and_then(|reference| reference.length(&contig)))
+    }
 }
 
 /// Feature movement handling
@@ -1007,6 +1456,649 @@ impl State {
     }
 }
 
+/// Feature search
+impl State {
+    /// Query features matching the attribute and go to the first match on the current contig.
+    async fn find_features(&mut self, query: FeatureQuery) -> Result<Vec<DataMessage>, TGVError> {
+        let track_service = match self.data.track_service.as_ref() {
+            Some(track_service) => track_service,
+            None => {
+                return Err(TGVError::StateError(
+                    "Feature query service not initialized".to_string(),
+                ))
+            }
+        };
+
+        let matches = track_service.query_feature_matches(&query).await?;
+        if matches.is_empty() {
+            self.feature_matches = None;
+            self.add_error_message(TGVError::StateError(format!(
+                "No genes with {}={}",
+                query.attribute, query.value
+            )));
+            return Ok(vec![]);
+        }
+
+        let contig = self.contig()?;
+        let middle = self.middle()?;
+        let mut feature_matches = FeatureMatches::new(query, matches);
+        let target = feature_matches.select_first(&contig, middle).cloned();
+        self.feature_matches = Some(feature_matches);
+
+        self.goto_feature_match(target)
+    }
+
+    /// Move k matches forward (negative: backward) through the last :find.
+    fn cycle_feature_matches(&mut self, k: isize) -> Result<Vec<DataMessage>, TGVError> {
+        let target = match self.feature_matches.as_mut() {
+            Some(feature_matches) => feature_matches.select_next(k).cloned(),
+            None => {
+                self.add_error_message(TGVError::StateError(
+                    "No search. Use :find attribute=value first.".to_string(),
+                ));
+                return Ok(vec![]);
+            }
+        };
+
+        self.goto_feature_match(target)
+    }
+
+    fn goto_feature_match(
+        &mut self,
+        target: Option<FeatureMatch>,
+    ) -> Result<Vec<DataMessage>, TGVError> {
+        let (target, feature_matches) = match (target, self.feature_matches.as_ref()) {
+            (Some(target), Some(feature_matches)) => (target, feature_matches),
+            _ => return Ok(vec![]),
+        };
+
+        let mut summary = format!(
+            "Match {}/{} for {}={}: {} at {}:{}",
+            feature_matches.current_index() + 1,
+            feature_matches.len(),
+            feature_matches.query.attribute,
+            feature_matches.query.value,
+            target.name,
+            target.contig.full_name(),
+            target.start
+        );
+        let contig = self.contig()?;
+        if target.contig.full_name() != contig.full_name() {
+            summary += &format!(" (switched from {})", contig.full_name());
+        }
+        self.errors.push(summary);
+
+        self.handle_movement_message(StateMessage::GotoContigCoordinate(
+            target.contig.full_name(),
+            target.start,
+        ))
+    }
+}
+
+/// Variant handling
+impl State {
+    fn handle_goto_variant_message(
+        &mut self,
+        variant: VariantLocus,
+    ) -> Result<Vec<DataMessage>, TGVError> {
+        self.handle_movement_message(StateMessage::GotoContigCoordinate(
+            variant.contig.clone(),
+            variant.position,
+        ))?;
+
+        // Variants are reviewed at base resolution.
+        let zoom = self.viewing_window()?.zoom();
+        let data_messages = self.handle_zoom_in(zoom)?;
+
+        self.pending_variant = Some(variant);
+        Ok(data_messages)
+    }
+
+    /// Note when the reference allele of a navigated variant does not match the reference genome.
+    fn check_variant_reference_allele(&mut self, variant: &VariantLocus) -> Result<(), TGVError> {
+        let sequence = match self.data.sequence.as_ref() {
+            Some(sequence) => sequence,
+            None => return Ok(()),
+        };
+
+        let region = Region {
+            contig: self.contig()?,
+            start: variant.position,
+            end: variant.position + variant.reference_allele.len() - 1,
+        };
+
+        if let Some(reference_sequence) = sequence.get_sequence(&region) {
+            if reference_sequence.to_uppercase() != variant.reference_allele {
+                self.errors.push(format!(
+                    "{}: reference allele is {}",
+                    variant,
+                    reference_sequence.to_uppercase()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Audit log
+impl State {
+    /// Record audited messages with the resulting viewing region. Write failures are reported
+    /// but do not stop the session.
+    fn record_audit(&mut self, audited_messages: Vec<(&str, String)>) {
+        if audited_messages.is_empty() {
+            return;
+        }
+        let region = self.viewing_region().ok();
+
+        let audit = match self.audit.as_mut() {
+            Some(audit) => audit,
+            None => return,
+        };
+        let errors = audited_messages
+            .iter()
+            .filter_map(|(event, message)| audit.record(event, message, region.as_ref()).err())
+            .collect::<Vec<TGVError>>();
+
+        for e in errors {
+            self.add_error_message(e);
+        }
+    }
+}
+
+/// Tiling
+impl State {
+    /// Start tiling from the current view. step: None for the width of the viewing window, so that
+    /// tiles do not overlap.
+    fn set_tile_step(&mut self, step: Option<usize>) -> Result<(), TGVError> {
+        let area = *self.current_frame_area()?;
+        let viewing_window = self.viewing_window()?;
+
+        let step = step.unwrap_or(viewing_window.width(&area));
+        self.tile_step = Some(step);
+        self.tile_origin = viewing_window.left();
+        self.errors.push(format!(
+            "Step: {} bp from {}:{}. ]s / [s to step.",
+            step,
+            viewing_window.contig.full_name(),
+            self.tile_origin
+        ));
+        Ok(())
+    }
+
+    /// Move by n tile steps. Negative n moves left.
+    fn step_tiles(&mut self, n: isize) -> Result<Vec<DataMessage>, TGVError> {
+        let step = match self.tile_step {
+            Some(step) => step,
+            None => {
+                self.add_error_message(TGVError::StateError(
+                    "No step set. Use :step [bases] first.".to_string(),
+                ));
+                return Ok(vec![]);
+            }
+        };
+
+        let area = *self.current_frame_area()?;
+        let contig_length = self.contig_length()?;
+        let viewing_window = self.viewing_window_mut()?;
+
+        let offset = n.unsigned_abs() * step;
+        let left = if n >= 0 {
+            viewing_window.left().saturating_add(offset)
+        } else {
+            viewing_window.left().saturating_sub(offset)
+        };
+        viewing_window.set_left(left, &area, contig_length);
+
+        let region = self.viewing_region()?;
+        self.errors.push(format!(
+            "Tile {} ({}), step {} bp",
+            get_tile_index(region.start, self.tile_origin, step),
+            region,
+            step
+        ));
+
+        self.get_data_requirements()
+    }
+}
+
+/// 0-based index of the tile starting at left. Tiles before the origin are negative. Views that
+/// are not aligned to a tile are counted in the tile they start in.
+fn get_tile_index(left: usize, origin: usize, step: usize) -> isize {
+    (left as isize - origin as isize).div_euclid(step as isize)
+}
+
+/// Assembly gaps
+impl State {
+    async fn handle_gap_movement_message(
+        &mut self,
+        message: StateMessage,
+    ) -> Result<Vec<DataMessage>, TGVError> {
+        let contig = self.contig()?;
+        let middle = self.middle()?;
+
+        let gap_index = match self.data.gap_index(&contig).await {
+            Ok(gap_index) => gap_index,
+            Err(e) => {
+                self.add_error_message(e);
+                return Ok(vec![]);
+            }
+        };
+
+        let target = match message {
+            StateMessage::GotoNextGap(n_movements) => {
+                if n_movements == 0 {
+                    return self.get_data_requirements();
+                }
+                gap_index.get_k_gaps_after(middle, n_movements)
+            }
+            StateMessage::GotoPreviousGap(n_movements) => {
+                if n_movements == 0 {
+                    return self.get_data_requirements();
+                }
+                gap_index.get_k_gaps_before(middle, n_movements)
+            }
+            _ => return Ok(vec![]),
+        };
+
+        match target.map(|gap| gap.start) {
+            Some(start) => self.handle_movement_message(StateMessage::GotoCoordinate(start)),
+            None => {
+                self.add_error_message(TGVError::StateError(format!(
+                    "No more gaps on {}",
+                    contig.full_name()
+                )));
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Summary of the gaps on the current contig, for display.
+    async fn list_gaps(&mut self) -> Result<String, TGVError> {
+        let contig = self.contig()?;
+        let gaps = self.data.gap_index(&contig).await?.gaps();
+
+        if gaps.is_empty() {
+            return Ok(format!(
+                "No gaps of at least {} bp on {}",
+                GapIndex::MIN_GAP_LENGTH,
+                contig.full_name()
+            ));
+        }
+
+        Ok(format!(
+            "{} gaps of at least {} bp on {}: {}",
+            gaps.len(),
+            GapIndex::MIN_GAP_LENGTH,
+            contig.full_name(),
+            gaps.iter()
+                .map(|gap| format!("{} ({} bp)", gap, gap.width()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))
+    }
+}
+
+/// Saved views
+impl State {
+    fn save_view(&mut self, slot: ViewSlot) -> Result<(), TGVError> {
+        let view = SavedView {
+            window: self.viewing_window()?.clone(),
+            display: DisplaySettings::from_settings(&self.settings),
+            track_dims: self.track_dims.clone(),
+            quick_filter: self.quick_filter.clone(),
+        };
+        self.saved_views.insert(slot, view);
+        self.current_view = Some(slot);
+        Ok(())
+    }
+
+    /// Restore a saved view. Reports an error if the slot is empty.
+    fn restore_view(&mut self, slot: ViewSlot) -> Result<Vec<DataMessage>, TGVError> {
+        let view = match self.saved_views.get(&slot) {
+            Some(view) => view.clone(),
+            None => {
+                self.add_error_message(TGVError::StateError(format!(
+                    "No view saved in slot {}. Save one with :view save {}",
+                    slot,
+                    slot.to_string().to_lowercase()
+                )));
+                return Ok(vec![]);
+            }
+        };
+
+        self.window = Some(view.window);
+        view.display.apply(&mut self.settings);
+        self.track_dims = view.track_dims;
+        self.quick_filter = view.quick_filter;
+        self.current_view = Some(slot);
+        self.self_correct_viewing_window();
+
+        self.get_data_requirements()
+    }
+}
+
+/// Guides
+impl State {
+    /// contig: None for the current contig.
+    fn add_guide(
+        &mut self,
+        contig: Option<String>,
+        position: usize,
+        label: Option<String>,
+    ) -> Result<(), TGVError> {
+        let contig = match contig {
+            Some(contig) => self.resolve_contig(&contig)?,
+            None => self.contig()?,
+        };
+
+        self.guides.push(Guide {
+            contig,
+            position,
+            label,
+        });
+        Ok(())
+    }
+}
+
+/// Read filter
+impl State {
+    /// Filter of reads drawn, counted in the consensus, and exported. The quick filter applies
+    /// only on its contig.
+    pub fn read_filter(&self) -> ReadFilter {
+        let mut filter = self.settings.read_filter();
+        if let (Some((contig, base_filter)), Ok(current_contig)) =
+            (self.quick_filter.as_ref(), self.contig())
+        {
+            if *contig == current_contig {
+                filter.base = Some(*base_filter);
+            }
+        }
+        filter
+    }
+
+    /// Coverage of the loaded reads admitted by the read filter, while a quick filter is active.
+    /// None otherwise: coverage then counts all reads, including downsampled ones.
+    pub fn quick_filtered_coverage(&self) -> Option<Alignment> {
+        let filter = self.read_filter();
+        if filter.base.is_none() {
+            return None;
+        }
+        self.data
+            .alignment
+            .as_ref()
+            .map(|alignment| alignment.filtered_coverage(&filter, &self.settings.library_type))
+    }
+
+    /// Filter reads by the base at the middle of the view: the most common non-reference base,
+    /// or the most common base if no reference is loaded.
+    /// carries: true to show only reads carrying the base, false to hide them.
+    fn set_quick_filter(&mut self, carries: bool) -> Result<(), TGVError> {
+        let alignment = match self.data.alignment.as_ref() {
+            Some(alignment) => alignment,
+            None => {
+                return Err(TGVError::StateError(
+                    "No alignments are loaded. Zoom in to load reads before filtering.".to_string(),
+                ))
+            }
+        };
+
+        let region = Region {
+            contig: self.contig()?,
+            start: self.middle()?,
+            end: self.middle()?,
+        };
+
+        let counts = count_bases(alignment, &region, &self.settings.read_filter())[0];
+        let reference_base = self
+            .data
+            .sequence
+            .as_ref()
+            .and_then(|sequence| sequence.get_sequence(&region))
+            .and_then(|reference| reference.bytes().next())
+            .map(|base| base.to_ascii_uppercase());
+
+        let base = match counts
+            .most_common_except(reference_base)
+            .or_else(|| counts.most_common_except(None))
+        {
+            Some(base) => base,
+            None => {
+                return Err(TGVError::StateError(format!(
+                    "No reads cover {}:{}",
+                    region.contig.full_name(),
+                    region.start
+                )))
+            }
+        };
+
+        self.quick_filter = Some((
+            region.contig.clone(),
+            BaseFilter {
+                position: region.start,
+                base,
+                carries,
+            },
+        ));
+        self.errors.push(format!(
+            "{} reads with {} at {}:{}. {} to invert, :filter clear to clear",
+            if carries { "Showing only" } else { "Hiding" },
+            base as char,
+            region.contig.full_name(),
+            region.start,
+            if carries { "F" } else { "f" },
+        ));
+        Ok(())
+    }
+
+    fn push_read_filter_message(&mut self) {
+        let message = match self.settings.min_mapq {
+            0 => "Showing reads of any MAPQ".to_string(),
+            min_mapq => format!(
+                "Hiding reads with MAPQ < {} (MAPQ 255: {})",
+                min_mapq, self.settings.mapq_255_meaning
+            ),
+        };
+        self.errors.push(message);
+    }
+}
+
+/// Insertions
+impl State {
+    /// Show the sequences inserted after the middle of the view, which are drawn collapsed.
+    fn show_insertions(&mut self) -> Result<(), TGVError> {
+        let alignment = match self.data.alignment.as_ref() {
+            Some(alignment) => alignment,
+            None => {
+                return Err(TGVError::StateError(
+                    "No alignments are loaded. Zoom in to load reads first.".to_string(),
+                ))
+            }
+        };
+
+        let position = self.middle()?;
+        let contig = self.contig()?;
+        let insertions = alignment.insertions_after(position, &self.read_filter());
+        if insertions.is_empty() {
+            return Err(TGVError::StateError(format!(
+                "No insertions after {}:{}",
+                contig.full_name(),
+                position
+            )));
+        }
+
+        self.errors.push(format!(
+            "Inserted after {}:{}: {}",
+            contig.full_name(),
+            position,
+            insertions
+                .iter()
+                .map(|(bases, n_reads)| format!(
+                    "{} ({} {})",
+                    String::from_utf8_lossy(bases),
+                    n_reads,
+                    if *n_reads == 1 { "read" } else { "reads" }
+                ))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+        Ok(())
+    }
+}
+
+/// Export
+impl State {
+    /// Write a text pileup of the viewing region to a file.
+    fn export_pileup(&self, path: &str) -> Result<(), TGVError> {
+        let alignment = match self.data.alignment.as_ref() {
+            Some(alignment) => alignment,
+            None => {
+                return Err(TGVError::StateError(
+                    "No alignments are loaded. Zoom in to load reads before exporting.".to_string(),
+                ))
+            }
+        };
+
+        let region = self.viewing_region()?;
+        if !alignment.has_complete_data(&region) {
+            return Err(TGVError::StateError(
+                "Reads in the visible region are not loaded. Zoom in before exporting.".to_string(),
+            ));
+        }
+
+        let reference = self
+            .data
+            .sequence
+            .as_ref()
+            .and_then(|sequence| sequence.get_sequence(&region));
+
+        std::fs::write(
+            path,
+            format_pileup(
+                alignment,
+                &region,
+                reference.as_deref(),
+                &self.read_filter(),
+            ),
+        )
+        .map_err(|e| TGVError::IOError(format!("Cannot write {}: {}", path, e)))
+    }
+
+    /// Longest sequence copied by :yankseq. Longer windows are truncated.
+    const MAX_YANK_LENGTH: usize = 100_000;
+
+    /// Send the reference sequence of the viewing region to the clipboard with OSC 52, and show it
+    /// in the console. Terminals may ignore OSC 52, so the console copy is always there.
+    fn yank_sequence(&mut self) -> Result<(), TGVError> {
+        let mut region = self.viewing_region()?;
+        if let Some(reference_length) = self.reference_contig_length()? {
+            if region.start > reference_length {
+                return Err(TGVError::StateError(
+                    "The visible region is beyond the end of the reference".to_string(),
+                ));
+            }
+            region.end = region.end.min(reference_length);
+        }
+
+        let width = region.width();
+        if width > Self::MAX_YANK_LENGTH {
+            region.end = region.start + Self::MAX_YANK_LENGTH - 1;
+        }
+
+        let sequence = match self
+            .data
+            .sequence
+            .as_ref()
+            .and_then(|sequence| sequence.get_sequence(&region))
+        {
+            Some(sequence) => sequence,
+            None => {
+                return Err(TGVError::StateError(
+                    "Sequence in the visible region is not loaded. Zoom in before copying."
+                        .to_string(),
+                ))
+            }
+        };
+
+        if width > Self::MAX_YANK_LENGTH {
+            self.errors.push(format!(
+                "Visible region is {} bp. Only the first {} bp are copied.",
+                width,
+                Self::MAX_YANK_LENGTH
+            ));
+        }
+
+        let mut stdout = std::io::stdout();
+        let sent = stdout
+            .write_all(osc52_copy_sequence(&sequence).as_bytes())
+            .and_then(|_| stdout.flush())
+            .is_ok();
+
+        self.errors.push(format!(">{}\n{}", region, sequence));
+        if sent {
+            self.errors.push(format!(
+                "{} bp ({}) sent to clipboard (OSC 52)",
+                sequence.len(),
+                region
+            ));
+        }
+        Ok(())
+    }
+
+    /// Command lines that reproduce the current view: a tgv command line, followed by commands
+    /// for state without a command-line option (soft clips in coverage, dimmed tracks, guides, and
+    /// the tiling step). The zoom level is not reproduced.
+    fn command_lines(&self) -> Result<Vec<String>, TGVError> {
+        let viewing_window = self.viewing_window()?;
+        let region = format!(
+            "{}:{}",
+            viewing_window.contig.full_name(),
+            viewing_window.middle(self.current_frame_area()?)
+        );
+
+        let mut lines = vec![self.settings.command_line(Some(&region))];
+        if self.settings.coverage_include_clips {
+            lines.push(":cov clips on".to_string());
+        }
+        for track in TrackKind::value_variants() {
+            let level = self.track_dim(*track);
+            if level > 0 {
+                lines.push(format!(":track dim {} {}", track, level));
+            }
+        }
+        for guide in self.guides.iter() {
+            let mut line = format!(":guide {}:{}", guide.contig.full_name(), guide.position);
+            if let Some(label) = &guide.label {
+                line.push(' ');
+                line.push_str(label);
+            }
+            lines.push(line);
+        }
+        if let Some(step) = self.tile_step {
+            lines.push(format!(":step {}", step));
+        }
+        Ok(lines)
+    }
+
+    /// Show the command lines that reproduce the current view, and copy them to the clipboard.
+    fn export_command_line(&mut self) -> Result<(), TGVError> {
+        let text = self.command_lines()?.join("\n");
+
+        let mut stdout = std::io::stdout();
+        let copied = stdout
+            .write_all(osc52_copy_sequence(&text).as_bytes())
+            .and_then(|_| stdout.flush())
+            .is_ok();
+
+        self.errors.push(text);
+        if copied {
+            self.errors
+                .push("Command line copied to the clipboard".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Looking for the default region
 impl State {
     const DEFAULT_GENE: &str = "KRAS";
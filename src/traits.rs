@@ -1,4 +1,5 @@
-use crate::models::contig::Contig;
+use crate::models::{contig::Contig, data::Data, view::TrackKind, window::ViewingWindow};
+use ratatui::{buffer::Buffer, layout::Rect};
 
 pub trait GenomeInterval {
     fn contig(&self) -> &Contig;
@@ -39,3 +40,17 @@ pub trait GenomeInterval {
         (self.start() + self.end()).div_ceil(2)
     }
 }
+
+/// A track drawn in its own rows below the reads and sequence.
+/// The App draws its renderers in order. New tracks are added to the list in App::new.
+pub trait TrackRenderer {
+    /// Rows taken by the track.
+    fn height(&self) -> u16;
+
+    /// Built-in track kind, for :track dim. None for custom tracks.
+    fn kind(&self) -> Option<TrackKind> {
+        None
+    }
+
+    fn render(&self, area: &Rect, buf: &mut Buffer, window: &ViewingWindow, data: &Data);
+}